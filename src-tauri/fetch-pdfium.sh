@@ -0,0 +1,50 @@
+#!/usr/bin/env bash
+
+# Download the PDFium library for this platform into resources/pdfium so it
+# is bundled with the app and PDF input works without a system libpdfium.
+#
+# Binaries come from https://github.com/bblanchon/pdfium-binaries. Set
+# PDFIUM_RELEASE to a release tag (for example chromium/7350) to pin one;
+# the latest release is used otherwise.
+
+set -euo pipefail
+
+cd "$(dirname "$0")"
+
+DEST="resources/pdfium"
+
+case "$(uname -s)-$(uname -m)" in
+  Darwin-arm64) ASSET="pdfium-mac-arm64.tgz"; LIBRARY="lib/libpdfium.dylib" ;;
+  Darwin-x86_64) ASSET="pdfium-mac-x64.tgz"; LIBRARY="lib/libpdfium.dylib" ;;
+  Linux-x86_64) ASSET="pdfium-linux-x64.tgz"; LIBRARY="lib/libpdfium.so" ;;
+  Linux-aarch64) ASSET="pdfium-linux-arm64.tgz"; LIBRARY="lib/libpdfium.so" ;;
+  MINGW*-x86_64 | MSYS*-x86_64) ASSET="pdfium-win-x64.tgz"; LIBRARY="bin/pdfium.dll" ;;
+  *)
+    echo "❌ No PDFium binary for $(uname -s) $(uname -m)"
+    exit 1
+    ;;
+esac
+
+if [ -f "$DEST/$(basename "$LIBRARY")" ]; then
+  echo "✓ PDFium already present in $DEST"
+  exit 0
+fi
+
+if [ -n "${PDFIUM_RELEASE:-}" ]; then
+  URL="https://github.com/bblanchon/pdfium-binaries/releases/download/$PDFIUM_RELEASE/$ASSET"
+else
+  URL="https://github.com/bblanchon/pdfium-binaries/releases/latest/download/$ASSET"
+fi
+
+WORK="$(mktemp -d)"
+trap 'rm -rf "$WORK"' EXIT
+
+echo "⬇️  Downloading $URL"
+curl --fail --location --silent --show-error "$URL" -o "$WORK/pdfium.tgz"
+tar -xzf "$WORK/pdfium.tgz" -C "$WORK"
+
+mkdir -p "$DEST"
+cp "$WORK/$LIBRARY" "$DEST/"
+cp "$WORK/LICENSE" "$DEST/LICENSE" 2>/dev/null || true
+
+echo "✓ PDFium installed to $DEST/$(basename "$LIBRARY")"
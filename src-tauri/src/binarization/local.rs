@@ -0,0 +1,383 @@
+//! Local (windowed) thresholding on integral images
+//!
+//! Window mean and variance are read from summed-area tables in O(1) per
+//! pixel, so the cost does not depend on the window size.
+
+use image::{DynamicImage, GrayImage, ImageBuffer, Rgba};
+
+/// Local thresholding method
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LocalThresholdMethod {
+    /// T = m · (1 + k · (s / R − 1))
+    Sauvola,
+    /// T = m + k · s
+    Niblack,
+    /// T = (1 − k) · m + k · M + k · (s / s_max) · (m − M)
+    WolfJolion,
+    /// Dark if I ≤ m · (1 − k)
+    BradleyRoth,
+    /// T = m + k · √(s² + m²)
+    Nick,
+}
+
+impl LocalThresholdMethod {
+    /// Commonly used `k` for each method
+    pub fn default_k(self) -> f32 {
+        match self {
+            LocalThresholdMethod::Sauvola => 0.5,
+            LocalThresholdMethod::Niblack => -0.2,
+            LocalThresholdMethod::WolfJolion => 0.5,
+            LocalThresholdMethod::BradleyRoth => 0.15,
+            LocalThresholdMethod::Nick => -0.1,
+        }
+    }
+
+    /// Default window size; Bradley-Roth uses 1/8 of the image width
+    pub fn default_window(self, width: u32) -> u32 {
+        match self {
+            LocalThresholdMethod::BradleyRoth => (width / 8).max(15) | 1,
+            _ => 15,
+        }
+    }
+
+    fn needs_variance(self) -> bool {
+        self != LocalThresholdMethod::BradleyRoth
+    }
+}
+
+/// Default dynamic range of the standard deviation for Sauvola
+pub const DEFAULT_SAUVOLA_R: f32 = 128.0;
+
+/// Parameters for local thresholding
+#[derive(Debug, Clone, Copy)]
+pub struct LocalThresholdParams {
+    /// Window side length in pixels
+    pub window_size: u32,
+    /// Method sensitivity
+    pub k: f32,
+    /// Dynamic range of the standard deviation (Sauvola only)
+    pub r: f32,
+}
+
+/// Summed-area tables of pixel values and squared values
+///
+/// Sums use wrapping `u32` arithmetic: window sums are differences of
+/// table entries and stay exact as long as the window sum itself fits,
+/// which holds for any window of fewer than 16M pixels.
+struct IntegralImages {
+    stride: usize,
+    sum: Vec<u32>,
+    sq_sum: Vec<u64>,
+}
+
+impl IntegralImages {
+    fn new(gray: &GrayImage, with_squares: bool) -> Self {
+        let (width, height) = gray.dimensions();
+        let stride = width as usize + 1;
+        let mut sum = vec![0u32; stride * (height as usize + 1)];
+        let mut sq_sum = if with_squares {
+            vec![0u64; sum.len()]
+        } else {
+            Vec::new()
+        };
+
+        for y in 0..height as usize {
+            let mut row_sum = 0u32;
+            let mut row_sq_sum = 0u64;
+            for x in 0..width as usize {
+                let value = gray.get_pixel(x as u32, y as u32).0[0];
+                row_sum = row_sum.wrapping_add(value as u32);
+                let index = (y + 1) * stride + x + 1;
+                sum[index] = sum[index - stride].wrapping_add(row_sum);
+                if with_squares {
+                    row_sq_sum += value as u64 * value as u64;
+                    sq_sum[index] = sq_sum[index - stride] + row_sq_sum;
+                }
+            }
+        }
+
+        Self {
+            stride,
+            sum,
+            sq_sum,
+        }
+    }
+
+    /// Mean and variance over the window `[x0, x1) × [y0, y1)`
+    fn window_stats(&self, x0: usize, y0: usize, x1: usize, y1: usize) -> (f32, f32) {
+        let (a, b, c, d) = (
+            y0 * self.stride + x0,
+            y0 * self.stride + x1,
+            y1 * self.stride + x0,
+            y1 * self.stride + x1,
+        );
+        let count = ((x1 - x0) * (y1 - y0)) as f64;
+        let sum = self.sum[d]
+            .wrapping_sub(self.sum[b])
+            .wrapping_sub(self.sum[c])
+            .wrapping_add(self.sum[a]) as f64;
+        let mean = sum / count;
+
+        if self.sq_sum.is_empty() {
+            return (mean as f32, 0.0);
+        }
+        let sq_sum = (self.sq_sum[d] + self.sq_sum[a] - self.sq_sum[b] - self.sq_sum[c]) as f64;
+        let variance = (sq_sum / count - mean * mean).max(0.0);
+        (mean as f32, variance as f32)
+    }
+}
+
+/// Apply a local thresholding method
+///
+/// # Arguments
+/// * `img` - The input image
+/// * `method` - Thresholding formula
+/// * `params` - Window size, `k` and `R`
+///
+/// # Returns
+/// A binarized image (text black, background white)
+pub fn apply_local_threshold(
+    img: &DynamicImage,
+    method: LocalThresholdMethod,
+    params: &LocalThresholdParams,
+) -> Result<DynamicImage, String> {
+    if params.window_size < 3 {
+        return Err(format!(
+            "Invalid threshold window: {} (expected at least 3)",
+            params.window_size
+        ));
+    }
+    if method == LocalThresholdMethod::Sauvola && params.r <= 0.0 {
+        return Err(format!("Invalid Sauvola R: {}", params.r));
+    }
+
+    let gray = img.to_luma8();
+    let (width, height) = gray.dimensions();
+    let integral = IntegralImages::new(&gray, method.needs_variance());
+
+    log::debug!(
+        "[{:?}] Processing with window={}, k={}, R={}",
+        method,
+        params.window_size,
+        params.k,
+        params.r
+    );
+
+    let half = (params.window_size / 2) as usize;
+    let window = |x: u32, y: u32| {
+        let (x, y) = (x as usize, y as usize);
+        (
+            x.saturating_sub(half),
+            y.saturating_sub(half),
+            (x + half + 1).min(width as usize),
+            (y + half + 1).min(height as usize),
+        )
+    };
+    let stats = |x: u32, y: u32| {
+        let (x0, y0, x1, y1) = window(x, y);
+        integral.window_stats(x0, y0, x1, y1)
+    };
+
+    // Wolf-Jolion normalizes by the image minimum and the largest local
+    // standard deviation
+    let (min_gray, max_std) = if method == LocalThresholdMethod::WolfJolion {
+        let min_gray = gray.pixels().map(|p| p.0[0]).min().unwrap_or(0) as f32;
+        let mut max_std = 0.0f32;
+        for y in 0..height {
+            for x in 0..width {
+                max_std = max_std.max(stats(x, y).1.sqrt());
+            }
+        }
+        (min_gray, max_std.max(f32::EPSILON))
+    } else {
+        (0.0, 1.0)
+    };
+
+    let k = params.k;
+    let mut output = ImageBuffer::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let (mean, variance) = stats(x, y);
+            let std_dev = variance.sqrt();
+
+            let threshold = match method {
+                LocalThresholdMethod::Sauvola => mean * (1.0 + k * (std_dev / params.r - 1.0)),
+                LocalThresholdMethod::Niblack => mean + k * std_dev,
+                LocalThresholdMethod::WolfJolion => {
+                    (1.0 - k) * mean + k * min_gray + k * (std_dev / max_std) * (mean - min_gray)
+                }
+                LocalThresholdMethod::BradleyRoth => mean * (1.0 - k),
+                LocalThresholdMethod::Nick => mean + k * (variance + mean * mean).sqrt(),
+            };
+
+            let pixel_val = gray.get_pixel(x, y).0[0] as f32;
+            let binary_val = if pixel_val > threshold { 255 } else { 0 };
+
+            output.put_pixel(x, y, Rgba([binary_val, binary_val, binary_val, 255]));
+        }
+    }
+
+    Ok(DynamicImage::ImageRgba8(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    const METHODS: [LocalThresholdMethod; 5] = [
+        LocalThresholdMethod::Sauvola,
+        LocalThresholdMethod::Niblack,
+        LocalThresholdMethod::WolfJolion,
+        LocalThresholdMethod::BradleyRoth,
+        LocalThresholdMethod::Nick,
+    ];
+
+    /// Deterministic noisy image with a dark diagonal band
+    fn synthetic_image(width: u32, height: u32) -> GrayImage {
+        let mut state = 12345u32;
+        GrayImage::from_fn(width, height, |x, y| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let noise = (state >> 16) % 60;
+            let base = if (x + y) % 9 < 3 { 40 } else { 180 };
+            Luma([(base + noise) as u8])
+        })
+    }
+
+    /// Mean and variance of the window clipped to the image, computed
+    /// directly
+    fn brute_force_stats(gray: &GrayImage, x: u32, y: u32, window: u32) -> (f64, f64) {
+        let half = (window / 2) as i64;
+        let (mut sum, mut sq_sum, mut count) = (0.0f64, 0.0f64, 0.0f64);
+        for wy in y as i64 - half..=y as i64 + half {
+            for wx in x as i64 - half..=x as i64 + half {
+                if wx < 0 || wy < 0 || wx >= gray.width() as i64 || wy >= gray.height() as i64 {
+                    continue;
+                }
+                let value = gray.get_pixel(wx as u32, wy as u32).0[0] as f64;
+                sum += value;
+                sq_sum += value * value;
+                count += 1.0;
+            }
+        }
+        let mean = sum / count;
+        (mean, (sq_sum / count - mean * mean).max(0.0))
+    }
+
+    #[test]
+    fn window_stats_match_brute_force() {
+        let gray = synthetic_image(23, 17);
+        let integral = IntegralImages::new(&gray, true);
+        // Includes a window larger than the image, clipped on every side
+        for window in [3u32, 7, 15, 41] {
+            let half = (window / 2) as usize;
+            for y in 0..gray.height() {
+                for x in 0..gray.width() {
+                    let (xu, yu) = (x as usize, y as usize);
+                    let (mean, variance) = integral.window_stats(
+                        xu.saturating_sub(half),
+                        yu.saturating_sub(half),
+                        (xu + half + 1).min(gray.width() as usize),
+                        (yu + half + 1).min(gray.height() as usize),
+                    );
+                    let (expected_mean, expected_variance) = brute_force_stats(&gray, x, y, window);
+                    assert!(
+                        (mean as f64 - expected_mean).abs() < 1e-3,
+                        "mean at ({}, {}), window {}",
+                        x,
+                        y,
+                        window
+                    );
+                    assert!(
+                        (variance as f64 - expected_variance).abs() < 1e-2,
+                        "variance at ({}, {}), window {}",
+                        x,
+                        y,
+                        window
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn methods_match_brute_force_thresholds() {
+        let gray = synthetic_image(29, 19);
+        let (width, height) = gray.dimensions();
+        let window = 9;
+        let min_gray = gray.pixels().map(|p| p.0[0]).min().unwrap() as f64;
+
+        for method in METHODS {
+            let params = LocalThresholdParams {
+                window_size: window,
+                k: method.default_k(),
+                r: DEFAULT_SAUVOLA_R,
+            };
+            let output =
+                apply_local_threshold(&DynamicImage::ImageLuma8(gray.clone()), method, &params)
+                    .unwrap()
+                    .to_luma8();
+
+            let max_std = (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| brute_force_stats(&gray, x, y, window).1.sqrt())
+                .fold(0.0f64, f64::max);
+            let k = params.k as f64;
+
+            let mut compared = 0;
+            for y in 0..height {
+                for x in 0..width {
+                    let (mean, variance) = brute_force_stats(&gray, x, y, window);
+                    let std_dev = variance.sqrt();
+                    let threshold = match method {
+                        LocalThresholdMethod::Sauvola => {
+                            mean * (1.0 + k * (std_dev / params.r as f64 - 1.0))
+                        }
+                        LocalThresholdMethod::Niblack => mean + k * std_dev,
+                        LocalThresholdMethod::WolfJolion => {
+                            (1.0 - k) * mean
+                                + k * min_gray
+                                + k * (std_dev / max_std) * (mean - min_gray)
+                        }
+                        LocalThresholdMethod::BradleyRoth => mean * (1.0 - k),
+                        LocalThresholdMethod::Nick => mean + k * (variance + mean * mean).sqrt(),
+                    };
+                    let value = gray.get_pixel(x, y).0[0] as f64;
+                    // Values within rounding distance of the threshold may
+                    // fall either way
+                    if (value - threshold).abs() < 1e-2 {
+                        continue;
+                    }
+                    let expected = if value > threshold { 255 } else { 0 };
+                    assert_eq!(
+                        output.get_pixel(x, y).0[0],
+                        expected,
+                        "{:?} at ({}, {})",
+                        method,
+                        x,
+                        y
+                    );
+                    compared += 1;
+                }
+            }
+            assert!(compared > (width * height) as usize * 9 / 10);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_parameters() {
+        let img = DynamicImage::ImageLuma8(synthetic_image(8, 8));
+        let params = LocalThresholdParams {
+            window_size: 1,
+            k: 0.5,
+            r: DEFAULT_SAUVOLA_R,
+        };
+        assert!(apply_local_threshold(&img, LocalThresholdMethod::Niblack, &params).is_err());
+        let params = LocalThresholdParams {
+            window_size: 5,
+            k: 0.5,
+            r: 0.0,
+        };
+        assert!(apply_local_threshold(&img, LocalThresholdMethod::Sauvola, &params).is_err());
+    }
+}
@@ -4,11 +4,52 @@
 //! - Adaptive threshold
 //! - Otsu's automatic threshold
 //! - Mean threshold
-//! - Sauvola's method for uneven illumination
+//! - Local methods for uneven illumination (Sauvola, Niblack,
+//!   Wolf-Jolion, Bradley-Roth, NICK) on integral images
 //! - CLAHE (Contrast Limited Adaptive Histogram Equalization)
 
+mod local;
+
+pub use local::{
+    apply_local_threshold, LocalThresholdMethod, LocalThresholdParams, DEFAULT_SAUVOLA_R,
+};
+
 use image::{DynamicImage, ImageBuffer, Rgba};
 use imageproc::contrast::adaptive_threshold;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Binarization method
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BinarizationMethod {
+    /// Keep the image in grayscale or color
+    None,
+    Otsu,
+    Adaptive,
+    Mean,
+    Sauvola,
+    Niblack,
+    /// Wolf-Jolion
+    Wolf,
+    /// Bradley-Roth
+    Bradley,
+    Nick,
+}
+
+impl BinarizationMethod {
+    /// The local thresholding formula, for windowed methods
+    pub fn local_method(self) -> Option<LocalThresholdMethod> {
+        match self {
+            BinarizationMethod::Sauvola => Some(LocalThresholdMethod::Sauvola),
+            BinarizationMethod::Niblack => Some(LocalThresholdMethod::Niblack),
+            BinarizationMethod::Wolf => Some(LocalThresholdMethod::WolfJolion),
+            BinarizationMethod::Bradley => Some(LocalThresholdMethod::BradleyRoth),
+            BinarizationMethod::Nick => Some(LocalThresholdMethod::Nick),
+            _ => None,
+        }
+    }
+}
 
 /// Apply adaptive threshold for better text recognition
 ///
@@ -36,32 +77,177 @@ pub fn apply_adaptive_threshold(img: &DynamicImage) -> Result<DynamicImage, Stri
     Ok(DynamicImage::ImageRgba8(output))
 }
 
+/// Default CLAHE tile grid (tiles per side)
+pub const DEFAULT_CLAHE_TILE_GRID: u32 = 8;
+
+/// Default CLAHE clip limit, as a multiple of the mean histogram bin height
+pub const DEFAULT_CLAHE_CLIP_LIMIT: f32 = 2.0;
+
+/// Check CLAHE parameters against their supported ranges
+pub fn validate_clahe_params(tile_grid: u32, clip_limit: f32) -> Result<(), String> {
+    if !(1..=64).contains(&tile_grid) {
+        return Err(format!(
+            "Invalid CLAHE tile grid: {} (expected 1-64)",
+            tile_grid
+        ));
+    }
+    if !(1.0..=40.0).contains(&clip_limit) {
+        return Err(format!(
+            "Invalid CLAHE clip limit: {} (expected 1.0-40.0)",
+            clip_limit
+        ));
+    }
+    Ok(())
+}
+
 /// Apply CLAHE (Contrast Limited Adaptive Histogram Equalization)
 ///
 /// Enhances local contrast while limiting noise amplification.
+/// Steps:
+/// 1. Split the image into a `tile_grid` x `tile_grid` grid
+/// 2. Build each tile's histogram, clip bins above the clip limit and
+///    redistribute the excess evenly
+/// 3. Turn each clipped histogram into an equalization lookup table
+/// 4. Map every pixel by bilinear interpolation between the lookup tables
+///    of the four nearest tile centers, avoiding seams at tile borders
 ///
 /// # Arguments
 /// * `img` - The input image
+/// * `tile_grid` - Number of tiles per side (1-64)
+/// * `clip_limit` - Clip limit relative to the mean bin height (1.0-40.0);
+///   1.0 leaves the image nearly unchanged, higher values add contrast
 ///
 /// # Returns
 /// An image with enhanced local contrast
-pub fn apply_clahe(img: &DynamicImage) -> Result<DynamicImage, String> {
-    use imageproc::contrast::equalize_histogram;
+pub fn apply_clahe(
+    img: &DynamicImage,
+    tile_grid: u32,
+    clip_limit: f32,
+) -> Result<DynamicImage, String> {
+    validate_clahe_params(tile_grid, clip_limit)?;
 
     let gray = img.to_luma8();
     let (width, height) = gray.dimensions();
+    if width == 0 || height == 0 {
+        return Ok(img.clone());
+    }
 
-    let equalized = equalize_histogram(&gray);
+    // Tiles must hold at least one pixel
+    let tiles_x = tile_grid.min(width);
+    let tiles_y = tile_grid.min(height);
+    let tile_start =
+        |index: u32, tiles: u32, size: u32| (index as u64 * size as u64 / tiles as u64) as u32;
+
+    log::debug!(
+        "[CLAHE] Processing with {}x{} tiles, clip limit {}",
+        tiles_x,
+        tiles_y,
+        clip_limit
+    );
+
+    // Step 1-3: Per-tile lookup tables
+    let mut luts = vec![[0u8; 256]; (tiles_x * tiles_y) as usize];
+    for ty in 0..tiles_y {
+        let (y0, y1) = (
+            tile_start(ty, tiles_y, height),
+            tile_start(ty + 1, tiles_y, height),
+        );
+        for tx in 0..tiles_x {
+            let (x0, x1) = (
+                tile_start(tx, tiles_x, width),
+                tile_start(tx + 1, tiles_x, width),
+            );
+
+            let mut histogram = [0u32; 256];
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    histogram[gray.get_pixel(x, y).0[0] as usize] += 1;
+                }
+            }
+
+            let lut = &mut luts[(ty * tiles_x + tx) as usize];
+            // A flat tile has no contrast to enhance; redistributing its
+            // clipped peak would only shift its level
+            if histogram.iter().filter(|&&count| count > 0).count() == 1 {
+                for (value, level) in lut.iter_mut().enumerate() {
+                    *level = value as u8;
+                }
+                continue;
+            }
+
+            let area = (x1 - x0) * (y1 - y0);
+            clip_histogram(&mut histogram, area, clip_limit);
+
+            let mut cumulative = 0u32;
+            for (value, count) in histogram.iter().enumerate() {
+                cumulative += count;
+                lut[value] = ((cumulative as f32 * 255.0 / area as f32).round()).min(255.0) as u8;
+            }
+        }
+    }
+
+    // Step 4: Bilinear interpolation between tile centers
+    // Position of a pixel in tile-center units, with its two neighbours
+    // and the weight of the second one
+    let neighbours = |pos: u32, tiles: u32, size: u32| {
+        let g = (pos as f32 + 0.5) * tiles as f32 / size as f32 - 0.5;
+        let g = g.clamp(0.0, (tiles - 1) as f32);
+        let low = g.floor() as u32;
+        let high = (low + 1).min(tiles - 1);
+        (low, high, g - low as f32)
+    };
+
+    let x_neighbours: Vec<(u32, u32, f32)> =
+        (0..width).map(|x| neighbours(x, tiles_x, width)).collect();
 
     let mut output = ImageBuffer::new(width, height);
-    for (x, y, pixel) in equalized.enumerate_pixels() {
-        let val = pixel.0[0];
-        output.put_pixel(x, y, Rgba([val, val, val, 255]));
+    for y in 0..height {
+        let (ty0, ty1, wy) = neighbours(y, tiles_y, height);
+        for x in 0..width {
+            let (tx0, tx1, wx) = x_neighbours[x as usize];
+            let value = gray.get_pixel(x, y).0[0] as usize;
+            let lookup = |tx: u32, ty: u32| luts[(ty * tiles_x + tx) as usize][value] as f32;
+
+            let top = lookup(tx0, ty0) * (1.0 - wx) + lookup(tx1, ty0) * wx;
+            let bottom = lookup(tx0, ty1) * (1.0 - wx) + lookup(tx1, ty1) * wx;
+            let val = (top * (1.0 - wy) + bottom * wy).round().clamp(0.0, 255.0) as u8;
+
+            output.put_pixel(x, y, Rgba([val, val, val, 255]));
+        }
     }
 
     Ok(DynamicImage::ImageRgba8(output))
 }
 
+/// Clip histogram bins and redistribute the excess over all bins
+fn clip_histogram(histogram: &mut [u32; 256], area: u32, clip_limit: f32) {
+    let limit = ((clip_limit * area as f32 / 256.0) as u32).max(1);
+
+    let mut excess = 0u32;
+    for count in histogram.iter_mut() {
+        if *count > limit {
+            excess += *count - limit;
+            *count = limit;
+        }
+    }
+
+    // Spread evenly, then hand out the remainder at regular intervals
+    let per_bin = excess / 256;
+    let remainder = excess % 256;
+    for count in histogram.iter_mut() {
+        *count += per_bin;
+    }
+    if let Some(step) = 256u32.checked_div(remainder) {
+        for count in histogram
+            .iter_mut()
+            .step_by(step.max(1) as usize)
+            .take(remainder as usize)
+        {
+            *count += 1;
+        }
+    }
+}
+
 /// Apply Otsu's automatic threshold
 ///
 /// Automatically calculates optimal threshold by maximizing
@@ -79,7 +265,7 @@ pub fn apply_otsu_threshold(img: &DynamicImage) -> Result<DynamicImage, String>
     let (width, height) = gray.dimensions();
 
     let threshold_value = calculate_otsu_threshold(&gray);
-    println!("[Otsu] Calculated threshold: {}", threshold_value);
+    log::debug!("[Otsu] Calculated threshold: {}", threshold_value);
 
     let thresholded = threshold(&gray, threshold_value, ThresholdType::Binary);
 
@@ -173,7 +359,7 @@ pub fn apply_mean_threshold(img: &DynamicImage) -> Result<DynamicImage, String>
     let sum: u64 = gray.pixels().map(|p| p.0[0] as u64).sum();
     let mean = (sum / (width * height) as u64) as u8;
 
-    println!("[Mean] Threshold: {}", mean);
+    log::debug!("[Mean] Threshold: {}", mean);
 
     let thresholded = threshold(&gray, mean, ThresholdType::Binary);
 
@@ -186,62 +372,68 @@ pub fn apply_mean_threshold(img: &DynamicImage) -> Result<DynamicImage, String>
     Ok(DynamicImage::ImageRgba8(output))
 }
 
-/// Apply Sauvola binarization
-///
-/// Better for documents with uneven illumination.
-/// Reference: Sauvola, J., & Pietikäinen, M. (2000)
-///
-/// # Arguments
-/// * `img` - The input image
-///
-/// # Returns
-/// A binarized image
-pub fn apply_sauvola_threshold(img: &DynamicImage) -> Result<DynamicImage, String> {
-    let gray = img.to_luma8();
-    let (width, height) = gray.dimensions();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    fn clahe(gray: &GrayImage, tile_grid: u32, clip_limit: f32) -> GrayImage {
+        apply_clahe(
+            &DynamicImage::ImageLuma8(gray.clone()),
+            tile_grid,
+            clip_limit,
+        )
+        .unwrap()
+        .to_luma8()
+    }
 
-    let window_size = 15;
-    let k = 0.5; // Sensitivity parameter (0.2-0.5)
-    let r = 128.0; // Dynamic range of standard deviation
+    fn spread(gray: &GrayImage) -> u8 {
+        let max = gray.pixels().map(|p| p.0[0]).max().unwrap();
+        let min = gray.pixels().map(|p| p.0[0]).min().unwrap();
+        max - min
+    }
 
-    let mut output = ImageBuffer::new(width, height);
+    #[test]
+    fn clip_limit_bounds_contrast_gain() {
+        // A faint texture spanning 16 gray levels
+        let faint =
+            GrayImage::from_fn(128, 128, |x, y| Luma([120 + ((x * 7 + y * 13) % 16) as u8]));
+        let input = spread(&faint) as f32;
+        // Each clipped bin, plus its share of the excess, bounds the gain
+        // per gray level at about the clip limit plus one
+        for clip_limit in [1.0, 2.0, 4.0] {
+            let output = spread(&clahe(&faint, 4, clip_limit)) as f32;
+            assert!(output > input, "clip limit {}: {}", clip_limit, output);
+            assert!(
+                output <= input * (clip_limit + 1.0),
+                "clip limit {}: {}",
+                clip_limit,
+                output
+            );
+        }
+        // Without effective clipping the texture is stretched to full range
+        assert!(spread(&clahe(&faint, 4, 40.0)) > 200);
+    }
 
-    println!(
-        "[Sauvola] Processing with window={}, k={}, R={}",
-        window_size, k, r
-    );
+    #[test]
+    fn uniform_image_is_unchanged() {
+        let flat = GrayImage::from_pixel(100, 80, Luma([173]));
+        for clip_limit in [1.0, 2.0, 40.0] {
+            assert_eq!(clahe(&flat, 8, clip_limit), flat);
+        }
+    }
 
-    for y in 0..height {
-        for x in 0..width {
-            let mut sum = 0.0;
-            let mut sq_sum = 0.0;
-            let mut count = 0;
-
-            let half_window = window_size as i32 / 2;
-            for dy in -half_window..=half_window {
-                for dx in -half_window..=half_window {
-                    let nx = (x as i32 + dx).clamp(0, width as i32 - 1) as u32;
-                    let ny = (y as i32 + dy).clamp(0, height as i32 - 1) as u32;
-                    let val = gray.get_pixel(nx, ny).0[0] as f32;
-                    sum += val;
-                    sq_sum += val * val;
-                    count += 1;
-                }
+    #[test]
+    fn tile_borders_leave_no_seams() {
+        let ramp = GrayImage::from_fn(256, 64, |x, _| Luma([x as u8]));
+        let out = clahe(&ramp, 4, 2.0);
+        // The ramp's slope changes gently across the 64 px tiles instead
+        // of jumping between neighbouring lookup tables
+        for y in [0, 31, 63] {
+            for x in 1..256 {
+                let step = out.get_pixel(x, y).0[0].abs_diff(out.get_pixel(x - 1, y).0[0]);
+                assert!(step <= 3, "step of {} at ({}, {})", step, x, y);
             }
-
-            let mean = sum / count as f32;
-            let variance = sq_sum / count as f32 - mean * mean;
-            let std_dev = variance.sqrt();
-
-            // Sauvola threshold formula
-            let threshold = mean * (1.0 + k * ((std_dev / r) - 1.0));
-
-            let pixel_val = gray.get_pixel(x, y).0[0] as f32;
-            let binary_val = if pixel_val > threshold { 255 } else { 0 };
-
-            output.put_pixel(x, y, image::Rgba([binary_val, binary_val, binary_val, 255]));
         }
     }
-
-    Ok(DynamicImage::ImageRgba8(output))
 }
@@ -0,0 +1,383 @@
+//! Document input module
+//!
+//! Enumerates the pages of an input file as images:
+//! - PDF pages, rasterized with PDFium at a configurable DPI
+//! - Every page (IFD) of a multi-page TIFF
+//! - Every frame of an animated GIF
+//! - Any other format supported by `image` as a single page
+//!
+//! Pages are handed to a callback one at a time so long documents are
+//! never held in memory all at once. EXIF (or TIFF) orientation is applied
+//! on load so pages match what image viewers display.
+
+use image::codecs::gif::GifDecoder;
+use image::metadata::Orientation;
+use image::{
+    AnimationDecoder, DynamicImage, GrayImage, ImageBuffer, ImageDecoder, ImageFormat, ImageReader,
+    Luma, LumaA, Rgb, Rgba,
+};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Default rasterization resolution for PDF pages
+pub const DEFAULT_PDF_DPI: f32 = 300.0;
+
+/// Orientation transform applied from image metadata
+///
+/// The page was rotated clockwise by `rotation_degrees`, then mirrored
+/// horizontally if `flip_horizontal` is set.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExifOrientation {
+    /// EXIF Orientation tag value (2-8)
+    pub exif_value: u8,
+    pub rotation_degrees: u32,
+    pub flip_horizontal: bool,
+}
+
+impl ExifOrientation {
+    /// Describe a non-identity orientation
+    fn from_orientation(orientation: Orientation) -> Option<Self> {
+        let (rotation_degrees, flip_horizontal) = match orientation {
+            Orientation::NoTransforms => return None,
+            Orientation::FlipHorizontal => (0, true),
+            Orientation::Rotate90 => (90, false),
+            Orientation::Rotate180 => (180, false),
+            Orientation::Rotate270 => (270, false),
+            // A vertical flip is a half turn followed by a horizontal flip
+            Orientation::FlipVertical => (180, true),
+            Orientation::Rotate90FlipH => (90, true),
+            Orientation::Rotate270FlipH => (270, true),
+        };
+        Some(Self {
+            exif_value: orientation.to_exif(),
+            rotation_degrees,
+            flip_horizontal,
+        })
+    }
+}
+
+/// A decoded input page
+pub struct InputPage {
+    /// 1-based page (or frame) number
+    pub number: u32,
+    /// Page image in display orientation
+    pub image: DynamicImage,
+    /// Metadata orientation applied on load, if any
+    pub orientation: Option<ExifOrientation>,
+    /// Resolution of the page in pixels per inch, when known: the
+    /// rasterization DPI of PDF pages or the resolution tags of TIFF pages
+    pub dpi: Option<f32>,
+}
+
+impl InputPage {
+    fn new(number: u32, image: DynamicImage) -> Self {
+        Self {
+            number,
+            image,
+            orientation: None,
+            dpi: None,
+        }
+    }
+
+    /// Create a page, applying its metadata orientation
+    fn oriented(number: u32, mut image: DynamicImage, orientation: Orientation) -> Self {
+        image.apply_orientation(orientation);
+        Self {
+            number,
+            image,
+            orientation: ExifOrientation::from_orientation(orientation),
+            dpi: None,
+        }
+    }
+
+    fn with_dpi(mut self, dpi: Option<f32>) -> Self {
+        self.dpi = dpi;
+        self
+    }
+}
+
+/// Options for reading input pages
+pub struct PageLoadOptions<'a> {
+    /// Resolution used to rasterize PDF pages
+    pub pdf_dpi: f32,
+    /// Directories searched for a bundled PDFium library before the system one
+    pub pdfium_dirs: &'a [PathBuf],
+}
+
+/// Visit every page of an input file in order
+///
+/// # Arguments
+/// * `path` - Path to the input file
+/// * `options` - Page loading options
+/// * `visit` - Called with each page in order
+///
+/// # Returns
+/// The number of pages visited; a document without pages is an error
+pub fn for_each_page<F>(path: &Path, options: &PageLoadOptions, mut visit: F) -> Result<u32, String>
+where
+    F: FnMut(InputPage) -> Result<(), String>,
+{
+    let page_count = if is_pdf(path)? {
+        for_each_pdf_page(path, options, visit)?
+    } else {
+        match ImageFormat::from_path(path).ok() {
+            Some(ImageFormat::Tiff) => for_each_tiff_page(path, visit)?,
+            Some(ImageFormat::Gif) => for_each_gif_frame(path, visit)?,
+            _ => {
+                visit(load_image(path)?)?;
+                1
+            }
+        }
+    };
+
+    if page_count == 0 {
+        return Err("No pages in document".to_string());
+    }
+    Ok(page_count)
+}
+
+/// Load a single image, applying its EXIF orientation
+fn load_image(path: &Path) -> Result<InputPage, String> {
+    let mut decoder = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Failed to load image: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Failed to load image: {}", e))?;
+    // Unreadable metadata is not worth failing the load over
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let img =
+        DynamicImage::from_decoder(decoder).map_err(|e| format!("Failed to load image: {}", e))?;
+    Ok(InputPage::oriented(1, img, orientation))
+}
+
+/// Check the file signature for a PDF header
+fn is_pdf(path: &Path) -> Result<bool, String> {
+    let mut header = [0u8; 5];
+    let mut file = File::open(path).map_err(|e| format!("Failed to open input: {}", e))?;
+    let read = file
+        .read(&mut header)
+        .map_err(|e| format!("Failed to read input: {}", e))?;
+    Ok(read == header.len() && &header == b"%PDF-")
+}
+
+/// Rasterize PDF pages with PDFium
+///
+/// PDFium is loaded at runtime: a library bundled in one of
+/// `options.pdfium_dirs` is preferred, falling back to the system library.
+/// When neither loads, PDF input fails with a "PDF support unavailable"
+/// error rather than the loader's message.
+fn for_each_pdf_page<F>(path: &Path, options: &PageLoadOptions, mut visit: F) -> Result<u32, String>
+where
+    F: FnMut(InputPage) -> Result<(), String>,
+{
+    use pdfium_render::prelude::{PdfRenderConfig, Pdfium};
+
+    if options.pdf_dpi <= 0.0 {
+        return Err(format!("Invalid PDF DPI: {}", options.pdf_dpi));
+    }
+
+    let bindings = options
+        .pdfium_dirs
+        .iter()
+        .map(Pdfium::pdfium_platform_library_name_at_path)
+        .filter(|library| library.exists())
+        .find_map(|library| Pdfium::bind_to_library(library).ok())
+        .map_or_else(Pdfium::bind_to_system_library, Ok)
+        .map_err(|e| {
+            log::warn!("[Input] Failed to load PDFium: {}", e);
+            "PDF support unavailable: the PDFium library was not found".to_string()
+        })?;
+    let pdfium = Pdfium::new(bindings);
+
+    let document = pdfium
+        .load_pdf_from_file(path, None)
+        .map_err(|e| format!("Failed to open PDF: {}", e))?;
+
+    let config = PdfRenderConfig::new().scale_page_by_factor(options.pdf_dpi / 72.0);
+    let mut page_count = 0;
+    for (index, page) in document.pages().iter().enumerate() {
+        let bitmap = page
+            .render_with_config(&config)
+            .map_err(|e| format!("Failed to render PDF page {}: {}", index + 1, e))?;
+        page_count += 1;
+        visit(InputPage::new(page_count, bitmap.as_image()).with_dpi(Some(options.pdf_dpi)))?;
+    }
+
+    Ok(page_count)
+}
+
+/// Decode every page of a multi-page TIFF, honoring per-page orientation
+fn for_each_tiff_page<F>(path: &Path, mut visit: F) -> Result<u32, String>
+where
+    F: FnMut(InputPage) -> Result<(), String>,
+{
+    use tiff::decoder::Decoder;
+    use tiff::tags::Tag;
+
+    let file = File::open(path).map_err(|e| format!("Failed to open TIFF: {}", e))?;
+    let mut decoder =
+        Decoder::new(BufReader::new(file)).map_err(|e| format!("Failed to read TIFF: {}", e))?;
+
+    let mut page_count = 0;
+    loop {
+        page_count += 1;
+        let orientation = decoder
+            .get_tag_u32(Tag::Orientation)
+            .ok()
+            .and_then(|value| Orientation::from_exif(value as u8))
+            .unwrap_or(Orientation::NoTransforms);
+        let dpi = tiff_dpi(&mut decoder);
+        let img = match decode_tiff_page(&mut decoder) {
+            Ok(img) => img,
+            // `image` decodes some single-page layouts the page decoder
+            // does not; such files loaded through it before
+            Err(_) if page_count == 1 && !decoder.more_images() => {
+                visit(load_image(path)?.with_dpi(dpi))?;
+                return Ok(1);
+            }
+            Err(e) => return Err(format!("Failed to decode TIFF page {}: {}", page_count, e)),
+        };
+        visit(InputPage::oriented(page_count, img, orientation).with_dpi(dpi))?;
+
+        if !decoder.more_images() {
+            break;
+        }
+        decoder
+            .next_image()
+            .map_err(|e| format!("Failed to read TIFF page {}: {}", page_count + 1, e))?;
+    }
+
+    Ok(page_count)
+}
+
+/// Horizontal resolution of the current TIFF page in pixels per inch
+fn tiff_dpi<R: std::io::Read + std::io::Seek>(
+    decoder: &mut tiff::decoder::Decoder<R>,
+) -> Option<f32> {
+    use tiff::decoder::ifd::Value;
+    use tiff::tags::Tag;
+
+    let resolution = match decoder.find_tag(Tag::XResolution).ok()?? {
+        Value::Rational(numerator, denominator) if numerator > 0 && denominator > 0 => {
+            numerator as f32 / denominator as f32
+        }
+        _ => return None,
+    };
+    // ResolutionUnit: 1 = none, 2 = inch (default), 3 = centimeter
+    match decoder.get_tag_u32(Tag::ResolutionUnit).unwrap_or(2) {
+        2 => Some(resolution),
+        3 => Some(resolution * 2.54),
+        _ => None,
+    }
+}
+
+/// Decode the current TIFF page into an 8-bit image
+fn decode_tiff_page<R: std::io::Read + std::io::Seek>(
+    decoder: &mut tiff::decoder::Decoder<R>,
+) -> Result<DynamicImage, String> {
+    use tiff::decoder::DecodingResult;
+    use tiff::ColorType;
+
+    let (width, height) = decoder.dimensions().map_err(|e| e.to_string())?;
+    let color_type = decoder.colortype().map_err(|e| e.to_string())?;
+    let data = decoder.read_image().map_err(|e| e.to_string())?;
+
+    let samples: Vec<u8> = match data {
+        DecodingResult::U8(samples) => samples,
+        DecodingResult::U16(samples) => samples.iter().map(|&v| (v >> 8) as u8).collect(),
+        _ => {
+            return Err(format!(
+                "Unsupported TIFF sample format for {:?}",
+                color_type
+            ))
+        }
+    };
+    let invalid = || "TIFF pixel data does not match its dimensions".to_string();
+
+    let img = match color_type {
+        ColorType::Gray(1) => {
+            // Bilevel rows are packed 8 pixels per byte, MSB first
+            let stride = width.div_ceil(8) as usize;
+            if samples.len() < stride * height as usize {
+                return Err(invalid());
+            }
+            let gray = GrayImage::from_fn(width, height, |x, y| {
+                let byte = samples[y as usize * stride + (x / 8) as usize];
+                let bit = (byte >> (7 - (x % 8))) & 1;
+                Luma([if bit == 1 { 255 } else { 0 }])
+            });
+            DynamicImage::ImageLuma8(gray)
+        }
+        ColorType::Gray(8) | ColorType::Gray(16) => DynamicImage::ImageLuma8(
+            ImageBuffer::<Luma<u8>, _>::from_raw(width, height, samples).ok_or_else(invalid)?,
+        ),
+        ColorType::RGB(8) | ColorType::RGB(16) => DynamicImage::ImageRgb8(
+            ImageBuffer::<Rgb<u8>, _>::from_raw(width, height, samples).ok_or_else(invalid)?,
+        ),
+        ColorType::RGBA(8) | ColorType::RGBA(16) => DynamicImage::ImageRgba8(
+            ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, samples).ok_or_else(invalid)?,
+        ),
+        // Gray with an extra (alpha) sample
+        ColorType::Multiband {
+            bit_depth: 8 | 16,
+            num_samples: 2,
+        } => DynamicImage::ImageLumaA8(
+            ImageBuffer::<LumaA<u8>, _>::from_raw(width, height, samples).ok_or_else(invalid)?,
+        ),
+        ColorType::CMYK(8) | ColorType::CMYK(16) => DynamicImage::ImageRgb8(
+            ImageBuffer::<Rgb<u8>, _>::from_raw(width, height, cmyk_to_rgb(&samples, false))
+                .ok_or_else(invalid)?,
+        ),
+        ColorType::CMYKA(8) | ColorType::CMYKA(16) => DynamicImage::ImageRgba8(
+            ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, cmyk_to_rgb(&samples, true))
+                .ok_or_else(invalid)?,
+        ),
+        other => return Err(format!("Unsupported TIFF color type: {:?}", other)),
+    };
+
+    Ok(img)
+}
+
+/// Convert CMYK (or CMYK plus alpha) samples to RGB (or RGBA)
+fn cmyk_to_rgb(samples: &[u8], alpha: bool) -> Vec<u8> {
+    let channels = if alpha { 5 } else { 4 };
+    let mut rgb = Vec::with_capacity(samples.len() / channels * (channels - 1));
+    for pixel in samples.chunks_exact(channels) {
+        let black = 255 - pixel[3] as u32;
+        for &ink in &pixel[..3] {
+            rgb.push(((255 - ink as u32) * black / 255) as u8);
+        }
+        if alpha {
+            rgb.push(pixel[4]);
+        }
+    }
+    rgb
+}
+
+/// Decode every frame of an animated GIF
+///
+/// Frames are composited onto the full canvas by the decoder.
+fn for_each_gif_frame<F>(path: &Path, mut visit: F) -> Result<u32, String>
+where
+    F: FnMut(InputPage) -> Result<(), String>,
+{
+    let file = File::open(path).map_err(|e| format!("Failed to open GIF: {}", e))?;
+    let decoder =
+        GifDecoder::new(BufReader::new(file)).map_err(|e| format!("Failed to read GIF: {}", e))?;
+
+    let mut page_count = 0;
+    for frame in decoder.into_frames() {
+        let frame =
+            frame.map_err(|e| format!("Failed to decode GIF frame {}: {}", page_count + 1, e))?;
+        page_count += 1;
+        visit(InputPage::new(
+            page_count,
+            DynamicImage::ImageRgba8(frame.into_buffer()),
+        ))?;
+    }
+
+    Ok(page_count)
+}
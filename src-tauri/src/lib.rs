@@ -4,19 +4,32 @@
 //! It provides image preprocessing, OCR, and utility functions.
 
 mod binarization;
+mod input;
 mod morphology;
 mod ocr;
+mod pdf;
+mod pipeline;
 mod preprocessing;
+mod presets;
 mod quality;
+mod table;
 
 use image::{DynamicImage, ImageBuffer, Rgba};
 use serde::Serialize;
 use std::fs;
+use std::path::PathBuf;
 use tauri::Manager;
-use tesseract::Tesseract;
 
-use ocr::{adaptive_preprocess, preprocess_image, OcrResult, ProcessingParams};
+use input::{for_each_page, InputPage, PageLoadOptions, DEFAULT_PDF_DPI};
+use ocr::{
+    recognize_best, recognize_page, render_layout, LayoutPage, OcrPageResult, OcrResult,
+    OutputFormat, PipelineStage, ProcessingParams, RecognizedPage, TessdataLocator, Trace,
+    AUTO_LANGUAGE,
+};
+use pipeline::Pipeline;
+use presets::{apply_overrides, Preset, PresetStore, SCREENSHOT_PRESET};
 use quality::assess_image_quality;
+use table::{detect_tables, render_tables, Table, TableFormat};
 
 /// Screenshot result containing path and OCR text
 #[derive(Debug, Serialize)]
@@ -25,59 +38,51 @@ struct ScreenshotResult {
     text: String,
 }
 
-const DEGRADED_ENGLISH_LANGUAGE: &str = "eng_degraded";
-
-fn bundled_tessdata_path(app: &tauri::AppHandle, language: &str) -> Result<Option<String>, String> {
-    if language != DEGRADED_ENGLISH_LANGUAGE {
-        return Ok(None);
+/// Tessdata locator searching bundled models before system ones
+fn tessdata_locator(app: &tauri::AppHandle) -> TessdataLocator {
+    let mut dirs = Vec::new();
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        dirs.push(resource_dir.join("tessdata"));
     }
+    dirs.push(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("resources")
+            .join("tessdata"),
+    );
+    TessdataLocator::new(dirs)
+}
 
-    let model_filename = format!("{}.traineddata", DEGRADED_ENGLISH_LANGUAGE);
-    let resource_dir = app
-        .path()
-        .resource_dir()
-        .map_err(|e| format!("Failed to resolve bundled OCR model directory: {}", e))?;
-    let development_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
-        .join("resources")
-        .join("tessdata");
-
-    for tessdata_dir in [resource_dir.join("tessdata"), development_dir] {
-        if tessdata_dir.join(&model_filename).exists() {
-            return Ok(Some(tessdata_dir.to_string_lossy().to_string()));
-        }
+/// Candidate directories for a bundled PDFium library
+fn bundled_pdfium_dirs(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        dirs.push(resource_dir.join("pdfium"));
     }
-
-    Err(format!(
-        "Bundled OCR model was not found: {}",
-        model_filename
-    ))
+    dirs.push(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("resources")
+            .join("pdfium"),
+    );
+    dirs
 }
 
-/// Perform OCR on an image with preprocessing
-#[tauri::command]
-fn perform_ocr(
-    app: tauri::AppHandle,
-    image_path: String,
-    params: ProcessingParams,
-) -> Result<OcrResult, String> {
+/// Preprocess and recognize a single page
+fn ocr_page(
+    page: InputPage,
+    params: &ProcessingParams,
+    tessdata: &TessdataLocator,
+    trace: &mut Trace,
+) -> Result<OcrPageResult, String> {
     use std::time::Instant;
-    let total_start = Instant::now();
 
-    // Initialize Tesseract with the specified language
-    let lang = if params.language.is_empty() {
-        "eng"
-    } else {
-        &params.language
-    };
-
-    // Load the image
-    let load_start = Instant::now();
-    let img =
-        image::open(&image_path).map_err(|e| format!("Failed to load image: {}", e))?;
-    println!(
-        "[Performance] Image loading took: {}ms",
-        load_start.elapsed().as_millis()
-    );
+    let InputPage {
+        number: page_number,
+        image: img,
+        orientation: exif_orientation,
+        ..
+    } = page;
+    let (image_width, image_height) = (img.width(), img.height());
+    trace.set_page(Some(page_number));
 
     // Assess image quality first
     let quality_metrics = if params.adaptive_mode {
@@ -86,17 +91,26 @@ fn perform_ocr(
         None
     };
 
-    // Apply preprocessing (adaptive or standard)
-    let preprocess_start = Instant::now();
-    let processed = if params.adaptive_mode {
-        adaptive_preprocess(img, &params)?
+    // Preprocess and recognize, trying candidate pipelines in best-of mode
+    let (recognized, best_of) = if params.best_of {
+        let (recognized, scores) = recognize_best(img, params, tessdata, trace)?;
+        (recognized, Some(scores))
     } else {
-        preprocess_image(img, &params)?
+        (recognize_page(img, params, tessdata, trace)?, None)
+    };
+    let RecognizedPage {
+        processed,
+        language,
+        script,
+        text,
+        blocks,
+    } = recognized;
+
+    let tables = if params.detect_tables {
+        detect_tables(&blocks, processed.table_grid.as_ref())
+    } else {
+        Vec::new()
     };
-    println!(
-        "[Performance] Image preprocessing took: {}ms",
-        preprocess_start.elapsed().as_millis()
-    );
 
     // Save processed image to temp file
     let save_start = Instant::now();
@@ -105,46 +119,149 @@ fn perform_ocr(
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap();
     let processed_path = temp_dir.join(format!(
-        "imagio_processed_{}_{}.png",
+        "imagio_processed_{}_{}_p{}.png",
         now.as_secs(),
-        now.subsec_nanos()
+        now.subsec_nanos(),
+        page_number
     ));
 
     processed
+        .image
         .save(&processed_path)
         .map_err(|e| format!("Failed to save processed image: {}", e))?;
-    println!(
-        "[Performance] Saving processed image took: {}ms",
-        save_start.elapsed().as_millis()
-    );
+    trace.timing("save", save_start.elapsed());
 
     let processed_path_str = processed_path.to_string_lossy().to_string();
 
-    // Perform OCR on processed image
-    let ocr_start = Instant::now();
-    let tessdata_path = bundled_tessdata_path(&app, lang)?;
-    let tesseract = Tesseract::new(tessdata_path.as_deref(), Some(lang))
-        .map_err(|e| format!("Failed to initialize Tesseract: {}", e))?;
-
-    let result = tesseract
-        .set_image(&processed_path_str)
-        .map_err(|e| format!("Failed to set image: {}", e))?
-        .get_text()
-        .map_err(|e| format!("Failed to extract text: {}", e))?;
-    println!(
-        "[Performance] Tesseract OCR took: {}ms",
-        ocr_start.elapsed().as_millis()
-    );
-
-    println!(
-        "[Performance] Total OCR operation took: {}ms",
-        total_start.elapsed().as_millis()
-    );
+    // Debug mode: save a thumbnail of every stage next to the result
+    let mut stages = Vec::with_capacity(processed.stages.len());
+    for (index, stage) in processed.stages.into_iter().enumerate() {
+        let thumbnail_path = temp_dir.join(format!(
+            "imagio_processed_{}_{}_p{}_s{}.png",
+            now.as_secs(),
+            now.subsec_nanos(),
+            page_number,
+            index + 1
+        ));
+        stage
+            .thumbnail
+            .save(&thumbnail_path)
+            .map_err(|e| format!("Failed to save stage thumbnail: {}", e))?;
+        stages.push(PipelineStage {
+            name: stage.name.to_string(),
+            params: stage.params,
+            duration_ms: stage.duration_ms,
+            thumbnail_path: thumbnail_path.to_string_lossy().to_string(),
+        });
+    }
 
-    Ok(OcrResult {
-        text: result,
+    Ok(OcrPageResult {
+        page_number,
+        text,
         processed_image_path: processed_path_str,
         quality_metrics,
+        language,
+        script,
+        exif_orientation,
+        orientation: processed.orientation,
+        blocks,
+        image_width,
+        image_height,
+        scale_factor: processed.scale_factor,
+        dewarp: processed.dewarp,
+        table_grid: processed.table_grid,
+        tables,
+        stages,
+        best_of,
+    })
+}
+
+/// Perform OCR on an image or multi-page document with preprocessing
+///
+/// PDFs, multi-page TIFFs and animated GIFs are processed page by page;
+/// the combined text of all pages is returned in `text`.
+///
+/// Parameters come either from `params` or from the named `preset`;
+/// `overrides` (partial parameters) are applied on top of either.
+///
+/// When `output_format` is hOCR, ALTO or TSV, the rendered document is
+/// returned in `formatted_output` alongside the plain text.
+#[tauri::command]
+fn perform_ocr(
+    app: tauri::AppHandle,
+    image_path: String,
+    params: Option<ProcessingParams>,
+    preset: Option<String>,
+    overrides: Option<serde_json::Value>,
+    output_format: Option<OutputFormat>,
+) -> Result<OcrResult, String> {
+    use std::time::Instant;
+    let total_start = Instant::now();
+
+    let params = match (params, preset) {
+        (Some(params), None) => params,
+        (None, Some(name)) => preset_store(&app)?.get(&name)?.params,
+        (Some(_), Some(_)) => return Err("Pass either params or a preset, not both".to_string()),
+        (None, None) => return Err("Missing processing params or preset".to_string()),
+    };
+    let params = match overrides {
+        Some(overrides) => apply_overrides(&params, &overrides)?,
+        None => params,
+    };
+
+    // Check the language and processing parameters before loading any pages
+    let tessdata = tessdata_locator(&app);
+    if params.language != AUTO_LANGUAGE && !params.language.is_empty() {
+        tessdata.resolve(&params.language)?;
+    }
+    params.validate()?;
+
+    let pdfium_dirs = bundled_pdfium_dirs(&app);
+    let load_options = PageLoadOptions {
+        pdf_dpi: params.pdf_dpi.unwrap_or(DEFAULT_PDF_DPI),
+        pdfium_dirs: &pdfium_dirs,
+    };
+
+    let mut pages = Vec::new();
+    let mut trace = Trace::new();
+    for_each_page(std::path::Path::new(&image_path), &load_options, |page| {
+        let page_number = page.number;
+        let page = ocr_page(page, &params, &tessdata, &mut trace)
+            .map_err(|e| format!("Page {}: {}", page_number, e))?;
+        pages.push(page);
+        Ok(())
+    })?;
+
+    let text = pages
+        .iter()
+        .map(|page| page.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let formatted_output = output_format
+        .filter(|format| *format != OutputFormat::Text)
+        .map(|format| {
+            let layout_pages: Vec<LayoutPage> = pages
+                .iter()
+                .map(|page| LayoutPage {
+                    image_path: &image_path,
+                    width: page.image_width,
+                    height: page.image_height,
+                    text: &page.text,
+                    blocks: &page.blocks,
+                })
+                .collect();
+            render_layout(format, &layout_pages)
+        });
+
+    trace.set_page(None);
+    trace.timing("total", total_start.elapsed());
+
+    Ok(OcrResult {
+        text,
+        pages,
+        formatted_output,
+        trace: trace.into_events(),
     })
 }
 
@@ -181,25 +298,16 @@ async fn take_screenshot(app: tauri::AppHandle) -> Result<ScreenshotResult, Stri
 
     let path_str = screenshot_path.to_string_lossy().to_string();
 
-    // Automatically perform OCR with best practice defaults
-    let params = ProcessingParams {
-        contrast: 1.3,
-        brightness: 0.0,
-        sharpness: 1.2,
-        binarization_method: "otsu".to_string(),
-        use_clahe: true,
-        gaussian_blur: 0.5,
-        bilateral_filter: false,
-        morphology: "none".to_string(),
-        language: "eng".to_string(),
-        correct_skew: true,
-        skew_method: "projection".to_string(),
-        remove_borders: true,
-        adaptive_mode: true,
-    };
-
-    let ocr_result = perform_ocr(app, path_str.clone(), params)
-        .map_err(|e| format!("Screenshot OCR failed: {}", e))?;
+    // Automatically perform OCR with the screenshot preset
+    let ocr_result = perform_ocr(
+        app,
+        path_str.clone(),
+        None,
+        Some(SCREENSHOT_PRESET.to_string()),
+        None,
+        None,
+    )
+    .map_err(|e| format!("Screenshot OCR failed: {}", e))?;
 
     Ok(ScreenshotResult {
         path: path_str,
@@ -207,13 +315,8 @@ async fn take_screenshot(app: tauri::AppHandle) -> Result<ScreenshotResult, Stri
     })
 }
 
-/// Save text to a file path
-#[tauri::command]
-async fn save_text_to_path(text: String, file_path: String) -> Result<(), String> {
-    use std::path::Path;
-
-    let path = Path::new(&file_path);
-
+/// Resolve a user-chosen output path, requiring its parent directory to exist
+fn resolve_output_path(path: &std::path::Path) -> Result<PathBuf, String> {
     let parent_dir = path
         .parent()
         .ok_or_else(|| "Invalid file path: no parent directory".to_string())?;
@@ -229,14 +332,146 @@ async fn save_text_to_path(text: String, file_path: String) -> Result<(), String
         .canonicalize()
         .map_err(|e| format!("Failed to resolve parent path: {}", e))?;
 
-    let canonical_path = canonical_parent
-        .join(path.file_name().ok_or_else(|| "Invalid file name".to_string())?);
+    Ok(canonical_parent
+        .join(path.file_name().ok_or_else(|| "Invalid file name".to_string())?))
+}
+
+/// Save text to a file path
+///
+/// `format` describes the content (e.g. `formattedOutput` from `perform_ocr`);
+/// its conventional extension is appended when the path has none.
+#[tauri::command]
+async fn save_text_to_path(
+    text: String,
+    file_path: String,
+    format: Option<OutputFormat>,
+) -> Result<(), String> {
+    let mut path = PathBuf::from(&file_path);
+    if let Some(format) = format {
+        if path.extension().is_none() {
+            path.set_extension(format.extension());
+        }
+    }
+
+    let canonical_path = resolve_output_path(&path)?;
 
     fs::write(&canonical_path, text).map_err(|e| format!("Failed to save file: {}", e))?;
 
     Ok(())
 }
 
+/// Save recognized tables to a file path
+///
+/// CSV and Markdown separate tables with a blank line; XLSX puts each
+/// table on its own sheet. The format's extension is appended when the
+/// path has none.
+#[tauri::command]
+async fn save_tables_to_path(
+    tables: Vec<Table>,
+    file_path: String,
+    format: TableFormat,
+) -> Result<(), String> {
+    if tables.is_empty() {
+        return Err("No tables to save".to_string());
+    }
+
+    let mut path = PathBuf::from(&file_path);
+    if path.extension().is_none() {
+        path.set_extension(format.extension());
+    }
+
+    let canonical_path = resolve_output_path(&path)?;
+
+    fs::write(&canonical_path, render_tables(format, &tables))
+        .map_err(|e| format!("Failed to save file: {}", e))?;
+
+    Ok(())
+}
+
+/// OCR one or more images and write them as a single searchable PDF
+///
+/// Each page shows the original image with an invisible text layer
+/// positioned over the recognized words. Multi-page inputs contribute
+/// all of their pages. Page size follows each page's own resolution (the
+/// rasterization DPI of PDF pages, or a TIFF's resolution tags); `dpi`
+/// (default 300) is used for images that do not record one.
+#[tauri::command]
+async fn create_searchable_pdf(
+    app: tauri::AppHandle,
+    image_paths: Vec<String>,
+    output_path: String,
+    params: ProcessingParams,
+    dpi: Option<f32>,
+) -> Result<(), String> {
+    if image_paths.is_empty() {
+        return Err("No images selected".to_string());
+    }
+
+    let canonical_path = resolve_output_path(std::path::Path::new(&output_path))?;
+
+    // Check the language and processing parameters before loading any pages
+    let tessdata = tessdata_locator(&app);
+    if params.language != AUTO_LANGUAGE && !params.language.is_empty() {
+        tessdata.resolve(&params.language)?;
+    }
+    params.validate()?;
+    let pdfium_dirs = bundled_pdfium_dirs(&app);
+    let default_dpi = dpi.unwrap_or(pdf::DEFAULT_DPI);
+
+    // Recognition and encoding block for seconds per page
+    let bytes = tauri::async_runtime::spawn_blocking(move || {
+        render_searchable_pdf(&image_paths, &params, &tessdata, &pdfium_dirs, default_dpi)
+    })
+    .await
+    .map_err(|e| format!("Failed to create PDF: {}", e))??;
+
+    fs::write(&canonical_path, bytes).map_err(|e| format!("Failed to save PDF: {}", e))?;
+
+    Ok(())
+}
+
+/// Recognize every page of the inputs and encode the searchable PDF
+fn render_searchable_pdf(
+    image_paths: &[String],
+    params: &ProcessingParams,
+    tessdata: &TessdataLocator,
+    pdfium_dirs: &[PathBuf],
+    default_dpi: f32,
+) -> Result<Vec<u8>, String> {
+    let load_options = PageLoadOptions {
+        pdf_dpi: params.pdf_dpi.unwrap_or(DEFAULT_PDF_DPI),
+        pdfium_dirs,
+    };
+
+    let mut images = Vec::new();
+    let mut layouts = Vec::new();
+    let mut trace = Trace::new();
+    for image_path in image_paths {
+        for_each_page(std::path::Path::new(image_path), &load_options, |page| {
+            let page_number = page.number;
+            let image = page.image.clone();
+            let dpi = page.dpi.unwrap_or(default_dpi);
+            let page = ocr_page(page, params, tessdata, &mut trace).map_err(|e| {
+                format!("OCR failed for {} page {}: {}", image_path, page_number, e)
+            })?;
+            images.push((image, dpi));
+            layouts.push(page.blocks);
+            Ok(())
+        })?;
+    }
+
+    let pages: Vec<pdf::PdfPage> = images
+        .iter()
+        .zip(&layouts)
+        .map(|((image, dpi), blocks)| pdf::PdfPage {
+            image,
+            blocks,
+            dpi: *dpi,
+        })
+        .collect();
+    pdf::write_searchable_pdf(&pages)
+}
+
 // ============================================
 // Automated Testing API
 // ============================================
@@ -250,6 +485,73 @@ struct AppHealthCheck {
     features: Vec<String>,
 }
 
+/// Default preprocessing pipeline for the given parameters
+///
+/// The frontend edits this list (reorder, repeat, disable) and sends it
+/// back as `params.pipeline`.
+#[tauri::command]
+fn get_default_pipeline(params: ProcessingParams) -> Pipeline {
+    Pipeline::from_params(&params)
+}
+
+/// JSON Schema of the processing parameters
+///
+/// Used to generate the TypeScript `ProcessingParams` type from the Rust
+/// definition.
+#[tauri::command]
+fn get_processing_params_schema() -> schemars::Schema {
+    schemars::schema_for!(ProcessingParams)
+}
+
+/// Preset store in the application config directory
+fn preset_store(app: &tauri::AppHandle) -> Result<PresetStore, String> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+    Ok(PresetStore::new(&config_dir))
+}
+
+/// List built-in and user presets
+#[tauri::command]
+fn list_presets(app: tauri::AppHandle) -> Result<Vec<Preset>, String> {
+    Ok(preset_store(&app)?.list())
+}
+
+/// Save a user preset, replacing any with the same name
+#[tauri::command]
+fn save_preset(app: tauri::AppHandle, preset: Preset) -> Result<(), String> {
+    preset_store(&app)?.save(vec![preset])?;
+    Ok(())
+}
+
+/// Delete a user preset; built-in presets cannot be deleted
+#[tauri::command]
+fn delete_preset(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    preset_store(&app)?.delete(&name)
+}
+
+/// Import presets from a JSON file holding one preset or an array
+///
+/// # Returns
+/// The number of presets imported
+#[tauri::command]
+fn import_presets(app: tauri::AppHandle, input_path: String) -> Result<usize, String> {
+    preset_store(&app)?.import(std::path::Path::new(&input_path))
+}
+
+/// Export the named presets, or all user presets when `names` is empty,
+/// to a JSON file
+#[tauri::command]
+fn export_presets(
+    app: tauri::AppHandle,
+    names: Vec<String>,
+    output_path: String,
+) -> Result<(), String> {
+    let canonical_path = resolve_output_path(std::path::Path::new(&output_path))?;
+    preset_store(&app)?.export(&names, &canonical_path)
+}
+
 /// Health check endpoint for automated testing
 #[tauri::command]
 fn health_check() -> Result<AppHealthCheck, String> {
@@ -266,6 +568,7 @@ fn health_check() -> Result<AppHealthCheck, String> {
             "ocr".to_string(),
             "screenshot".to_string(),
             "image_processing".to_string(),
+            "searchable_pdf".to_string(),
         ],
     })
 }
@@ -308,23 +611,16 @@ async fn run_automated_test(
         test_path.to_string_lossy().to_string()
     };
 
-    let params = ProcessingParams {
-        contrast: 1.3,
-        brightness: 0.0,
-        sharpness: 1.2,
-        binarization_method: "otsu".to_string(),
-        use_clahe: true,
-        gaussian_blur: 0.5,
-        bilateral_filter: false,
-        morphology: "none".to_string(),
-        language: "eng".to_string(),
-        correct_skew: true,
-        skew_method: "projection".to_string(),
-        remove_borders: false,
-        adaptive_mode: true,
-    };
-
-    match perform_ocr(app, image_path, params) {
+    // The automated test has always run the screenshot settings with
+    // border removal off
+    match perform_ocr(
+        app,
+        image_path,
+        None,
+        Some(SCREENSHOT_PRESET.to_string()),
+        Some(serde_json::json!({ "removeBorders": false })),
+        None,
+    ) {
         Ok(result) => {
             let duration = start.elapsed();
             Ok(TestImageResult {
@@ -357,13 +653,13 @@ async fn copy_image_from_bytes(image_bytes: Vec<u8>) -> Result<(), String> {
     let t0 = Instant::now();
     let img = image::load_from_memory(&image_bytes)
         .map_err(|e| format!("Failed to decode image: {}", e))?;
-    println!("[Performance] Decode: {}ms", t0.elapsed().as_millis());
+    log::info!("[Performance] Decode: {}ms", t0.elapsed().as_millis());
 
     let t0 = Instant::now();
     let rgba_img = img.to_rgba8();
     let (width, height) = rgba_img.dimensions();
     let raw_data = rgba_img.into_raw();
-    println!(
+    log::info!(
         "[Performance] Convert to RGBA: {}ms ({}x{})",
         t0.elapsed().as_millis(),
         width,
@@ -384,11 +680,11 @@ async fn copy_image_from_bytes(image_bytes: Vec<u8>) -> Result<(), String> {
         .set_image(img_data)
         .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
 
-    println!(
+    log::info!(
         "[Performance] Clipboard write: {}ms",
         t0.elapsed().as_millis()
     );
-    println!("[Performance] TOTAL: {}ms", start.elapsed().as_millis());
+    log::info!("[Performance] TOTAL: {}ms", start.elapsed().as_millis());
 
     Ok(())
 }
@@ -414,7 +710,7 @@ fn cleanup_old_temp_files() {
                         if let Ok(age) = now.duration_since(modified) {
                             if age > max_age {
                                 let _ = fs::remove_file(&path);
-                                println!("[Cleanup] Removed old temp file: {:?}", path);
+                                log::info!("[Cleanup] Removed old temp file: {:?}", path);
                             }
                         }
                     }
@@ -435,13 +731,14 @@ pub fn run() {
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .setup(|app| {
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
+            // Registered in release builds too so OCR logs are not lost
+            let level = if cfg!(debug_assertions) {
+                log::LevelFilter::Debug
+            } else {
+                log::LevelFilter::Info
+            };
+            app.handle()
+                .plugin(tauri_plugin_log::Builder::default().level(level).build())?;
 
             Ok(())
         })
@@ -449,6 +746,15 @@ pub fn run() {
             perform_ocr,
             take_screenshot,
             save_text_to_path,
+            save_tables_to_path,
+            create_searchable_pdf,
+            get_default_pipeline,
+            get_processing_params_schema,
+            list_presets,
+            save_preset,
+            delete_preset,
+            import_presets,
+            export_presets,
             health_check,
             run_automated_test,
             copy_image_from_bytes
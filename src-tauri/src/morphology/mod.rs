@@ -9,6 +9,37 @@
 use image::{DynamicImage, ImageBuffer, Rgba};
 use imageproc::distance_transform::Norm;
 use imageproc::morphology::{dilate, erode};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Morphological operation with a 3×3 kernel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MorphologyOperation {
+    None,
+    Erode,
+    Dilate,
+    Opening,
+    Closing,
+}
+
+/// Apply a morphological operation
+///
+/// # Arguments
+/// * `img` - The input image
+/// * `operation` - Operation to apply
+///
+/// # Returns
+/// The processed image (unchanged for `None`)
+pub fn apply_morphology(img: &DynamicImage, operation: MorphologyOperation) -> DynamicImage {
+    match operation {
+        MorphologyOperation::None => img.clone(),
+        MorphologyOperation::Erode => apply_erosion(img),
+        MorphologyOperation::Dilate => apply_dilation(img),
+        MorphologyOperation::Opening => apply_opening(img),
+        MorphologyOperation::Closing => apply_closing(img),
+    }
+}
 
 /// Apply erosion morphological operation
 ///
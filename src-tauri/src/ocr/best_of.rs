@@ -0,0 +1,384 @@
+//! Best-of mode
+//!
+//! Instead of trusting fixed thresholds, runs a small set of candidate
+//! pipelines (global vs local binarization, with and without deskew) in
+//! parallel and keeps the one Tesseract reads best, judged by mean word
+//! confidence and the share of words found in a dictionary, weighted by
+//! how much of the text the candidate recognized compared with the others.
+
+use image::DynamicImage;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use super::{recognize_page, OcrBlock, ProcessingParams, RecognizedPage, TessdataLocator, Trace};
+use crate::binarization::BinarizationMethod;
+use crate::pipeline::{Binarize, Deskew, Pipeline, PipelineStep, StepConfig};
+use crate::preprocessing::SkewMethod;
+
+/// Binarization methods tried in best-of mode
+const CANDIDATE_BINARIZATIONS: [BinarizationMethod; 2] =
+    [BinarizationMethod::Otsu, BinarizationMethod::Sauvola];
+
+/// Weight of the dictionary hit rate in the combined score; the rest is
+/// mean word confidence
+const DICTIONARY_WEIGHT: f32 = 0.4;
+
+/// Word lists shipped with macOS and most Linux distributions
+const DICTIONARY_PATHS: [&str; 2] = ["/usr/share/dict/words", "/usr/dict/words"];
+
+/// Score of one candidate pipeline
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateScore {
+    /// Short description of the variant, e.g. "sauvola, no deskew"
+    pub label: String,
+    /// Mean Tesseract word confidence, 0-100
+    pub mean_confidence: f32,
+    /// Share of dictionary-checkable words found in the dictionary, when a
+    /// dictionary is available
+    pub dictionary_hit_rate: Option<f32>,
+    pub word_count: usize,
+    /// Characters in the recognized words
+    pub character_count: usize,
+    /// Combined score, 0.0-1.0: confidence and dictionary hits, scaled by
+    /// the character count relative to the candidate that read the most
+    pub score: f32,
+}
+
+/// Outcome of best-of mode for one page
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BestOfResult {
+    pub winner: CandidateScore,
+    /// Scores of the other candidates, best first
+    pub others: Vec<CandidateScore>,
+}
+
+/// System word list, loaded on first use
+fn dictionary() -> Option<&'static HashSet<String>> {
+    static DICTIONARY: OnceLock<Option<HashSet<String>>> = OnceLock::new();
+    DICTIONARY
+        .get_or_init(|| {
+            DICTIONARY_PATHS.iter().find_map(|path| {
+                let words = std::fs::read_to_string(path).ok()?;
+                Some(
+                    words
+                        .lines()
+                        .map(|word| word.trim().to_lowercase())
+                        .collect(),
+                )
+            })
+        })
+        .as_ref()
+}
+
+/// Set the method of every binarization step, adding one at the end if
+/// the pipeline has none
+fn with_binarization(pipeline: &Pipeline, method: BinarizationMethod) -> Pipeline {
+    let mut pipeline = pipeline.clone();
+    let mut found = false;
+    for entry in &mut pipeline.steps {
+        if let StepConfig::Binarize(step) = &mut entry.config {
+            step.method = method;
+            entry.enabled = true;
+            found = true;
+        }
+    }
+    if !found {
+        pipeline.steps.push(PipelineStep {
+            enabled: true,
+            config: StepConfig::Binarize(Binarize {
+                method,
+                window: None,
+                k: None,
+                r: None,
+            }),
+        });
+    }
+    pipeline
+}
+
+/// Enable or disable deskewing, adding a projection deskew step before
+/// the first non-geometric step if the pipeline has none
+fn with_deskew(pipeline: &Pipeline, enabled: bool) -> Pipeline {
+    let mut pipeline = pipeline.clone();
+    let mut found = false;
+    for entry in &mut pipeline.steps {
+        if let StepConfig::Deskew(_) = entry.config {
+            entry.enabled = enabled;
+            found = true;
+        }
+    }
+    if !found && enabled {
+        let index = pipeline
+            .steps
+            .iter()
+            .position(|entry| {
+                !matches!(
+                    entry.config,
+                    StepConfig::Perspective(_)
+                        | StepConfig::RemoveBorders(_)
+                        | StepConfig::Orientation(_)
+                )
+            })
+            .unwrap_or(pipeline.steps.len());
+        pipeline.steps.insert(
+            index,
+            PipelineStep {
+                enabled: true,
+                config: StepConfig::Deskew(Deskew {
+                    method: SkewMethod::Projection,
+                }),
+            },
+        );
+    }
+    pipeline
+}
+
+/// Candidate pipelines derived from the requested one
+fn candidate_pipelines(params: &ProcessingParams) -> Vec<(String, Pipeline)> {
+    let base = match &params.pipeline {
+        Some(pipeline) => pipeline.clone(),
+        None => Pipeline::from_params(params),
+    };
+
+    let mut candidates = Vec::new();
+    for method in CANDIDATE_BINARIZATIONS {
+        let binarized = with_binarization(&base, method);
+        for deskew in [true, false] {
+            let label = format!(
+                "{:?}, {}",
+                method,
+                if deskew { "deskew" } else { "no deskew" }
+            )
+            .to_lowercase();
+            candidates.push((label, with_deskew(&binarized, deskew)));
+        }
+    }
+    candidates
+}
+
+/// Score recognized words by confidence and dictionary hits
+///
+/// The score is the reading quality alone until [`weight_by_coverage`]
+/// compares the candidates.
+fn score_blocks(label: String, blocks: &[OcrBlock]) -> CandidateScore {
+    let words: Vec<_> = blocks
+        .iter()
+        .flat_map(|block| &block.paragraphs)
+        .flat_map(|paragraph| &paragraph.lines)
+        .flat_map(|line| &line.words)
+        .collect();
+
+    let mean_confidence = if words.is_empty() {
+        0.0
+    } else {
+        words.iter().map(|word| word.confidence).sum::<f32>() / words.len() as f32
+    };
+
+    // Only alphabetic words can be checked against the word list
+    let dictionary_hit_rate = dictionary().and_then(|dictionary| {
+        let checkable: Vec<String> = words
+            .iter()
+            .map(|word| {
+                word.text
+                    .trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .filter(|word| word.len() >= 2 && word.chars().all(|c| c.is_ascii_alphabetic()))
+            .collect();
+        if checkable.is_empty() {
+            return None;
+        }
+        let hits = checkable
+            .iter()
+            .filter(|word| dictionary.contains(word.as_str()))
+            .count();
+        Some(hits as f32 / checkable.len() as f32)
+    });
+
+    let confidence = mean_confidence / 100.0;
+    let score = match dictionary_hit_rate {
+        Some(rate) => (1.0 - DICTIONARY_WEIGHT) * confidence + DICTIONARY_WEIGHT * rate,
+        None => confidence,
+    };
+
+    CandidateScore {
+        label,
+        mean_confidence,
+        dictionary_hit_rate,
+        word_count: words.len(),
+        character_count: words.iter().map(|word| word.text.chars().count()).sum(),
+        score,
+    }
+}
+
+/// Scale each score by the share of text the candidate recognized
+///
+/// A binarization that wipes out most of the page can leave a few words
+/// read with high confidence; weighting by characters read relative to
+/// the most complete candidate keeps it from beating one that read the
+/// whole page slightly less confidently.
+fn weight_by_coverage(scores: &mut [&mut CandidateScore]) {
+    let most = scores
+        .iter()
+        .map(|score| score.character_count)
+        .max()
+        .unwrap_or(0);
+    if most == 0 {
+        return;
+    }
+    for score in scores.iter_mut() {
+        score.score *= score.character_count as f32 / most as f32;
+    }
+}
+
+/// Recognize a page with every candidate pipeline and keep the best
+///
+/// Candidates run in parallel, each with its own Tesseract instance.
+/// Adaptive mode is not applied to candidates since they replace the
+/// pipeline it would tune.
+///
+/// # Arguments
+/// * `img` - The page image
+/// * `params` - Processing parameters the candidates are derived from
+/// * `tessdata` - Locator for installed models
+/// * `trace` - Receives the winner's timings and the selection decision
+///
+/// # Returns
+/// The winning recognition and the scores of all candidates
+pub fn recognize_best(
+    img: DynamicImage,
+    params: &ProcessingParams,
+    tessdata: &TessdataLocator,
+    trace: &mut Trace,
+) -> Result<(RecognizedPage, BestOfResult), String> {
+    let candidates = candidate_pipelines(params);
+
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = candidates
+            .into_iter()
+            .map(|(label, pipeline)| {
+                let img = img.clone();
+                let candidate_params = ProcessingParams {
+                    pipeline: Some(pipeline),
+                    adaptive_mode: false,
+                    best_of: false,
+                    ..params.clone()
+                };
+                let mut candidate_trace = trace.fork();
+                scope.spawn(move || {
+                    let result =
+                        recognize_page(img, &candidate_params, tessdata, &mut candidate_trace);
+                    (label, result, candidate_trace)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join()).collect()
+    });
+
+    let mut scored = Vec::new();
+    let mut first_error = None;
+    for result in results {
+        match result {
+            Ok((label, Ok(page), candidate_trace)) => {
+                let score = score_blocks(label, &page.blocks);
+                scored.push((score, page, candidate_trace));
+            }
+            Ok((label, Err(e), _)) => {
+                log::warn!("[BestOf] Candidate {} failed: {}", label, e);
+                first_error.get_or_insert(e);
+            }
+            Err(_) => {
+                first_error.get_or_insert("Candidate pipeline panicked".to_string());
+            }
+        }
+    }
+
+    weight_by_coverage(
+        &mut scored
+            .iter_mut()
+            .map(|(score, _, _)| score)
+            .collect::<Vec<_>>(),
+    );
+    scored.sort_by(|a, b| b.0.score.total_cmp(&a.0.score));
+    let mut scored = scored.into_iter();
+    let Some((winner, page, winner_trace)) = scored.next() else {
+        return Err(first_error.unwrap_or_else(|| "No candidate pipelines".to_string()));
+    };
+    let others: Vec<CandidateScore> = scored.map(|(score, _, _)| score).collect();
+
+    trace.merge(winner_trace);
+    trace.decision(
+        "bestOf",
+        format!(
+            "{} scored {:.3} (confidence {:.1}, {} characters, dictionary {}) over {} other candidate(s)",
+            winner.label,
+            winner.score,
+            winner.mean_confidence,
+            winner.character_count,
+            winner
+                .dictionary_hit_rate
+                .map(|rate| format!("{:.0}%", rate * 100.0))
+                .unwrap_or_else(|| "n/a".to_string()),
+            others.len()
+        ),
+    );
+
+    Ok((page, BestOfResult { winner, others }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::parse_tsv;
+    use crate::preprocessing::CoordinateMapper;
+
+    /// One line of `count` copies of `word` at the given confidence
+    fn blocks(word: &str, count: usize, confidence: f32) -> Vec<OcrBlock> {
+        let mut tsv = String::from(
+            "2\t1\t1\t0\t0\t0\t0\t0\t900\t20\t-1\t\n\
+             3\t1\t1\t1\t0\t0\t0\t0\t900\t20\t-1\t\n\
+             4\t1\t1\t1\t1\t0\t0\t0\t900\t20\t-1\t\n",
+        );
+        for i in 0..count {
+            tsv.push_str(&format!(
+                "5\t1\t1\t1\t1\t{}\t{}\t0\t3\t20\t{}\t{}\n",
+                i + 1,
+                i * 3,
+                confidence,
+                word
+            ));
+        }
+        parse_tsv(&tsv, &CoordinateMapper::new(1000, 100))
+    }
+
+    #[test]
+    fn full_page_beats_a_few_confident_words() {
+        let mut sparse = score_blocks("sparse".to_string(), &blocks("the", 2, 96.0));
+        let mut full = score_blocks("full".to_string(), &blocks("the", 300, 85.0));
+        assert!(sparse.score > full.score);
+
+        weight_by_coverage(&mut [&mut sparse, &mut full]);
+        assert!(full.score > sparse.score);
+        assert_eq!(full.character_count, 900);
+    }
+
+    #[test]
+    fn similar_coverage_is_decided_by_confidence() {
+        let mut clean = score_blocks("clean".to_string(), &blocks("the", 290, 90.0));
+        let mut noisy = score_blocks("noisy".to_string(), &blocks("the", 300, 60.0));
+
+        weight_by_coverage(&mut [&mut clean, &mut noisy]);
+        assert!(clean.score > noisy.score);
+    }
+
+    #[test]
+    fn no_text_leaves_scores_unchanged() {
+        let mut empty = score_blocks("empty".to_string(), &[]);
+        weight_by_coverage(&mut [&mut empty]);
+        assert_eq!(empty.score, 0.0);
+        assert_eq!(empty.character_count, 0);
+    }
+}
@@ -0,0 +1,203 @@
+//! Tesseract engine configuration
+//!
+//! Typed, validated options for page segmentation, engine mode, character
+//! filtering and arbitrary Tesseract variables.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tesseract::{OcrEngineMode, PageSegMode, Tesseract};
+
+/// Tesseract page segmentation mode (`--psm`, 1-13)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(try_from = "u8")]
+pub struct PageSegmentationMode(u8);
+
+impl TryFrom<u8> for PageSegmentationMode {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            // PSM 0 only detects orientation and script and produces no text
+            0 => Err("Page segmentation mode 0 (OSD only) does not produce text".to_string()),
+            1..=13 => Ok(Self(value)),
+            _ => Err(format!(
+                "Invalid page segmentation mode: {} (expected 1-13)",
+                value
+            )),
+        }
+    }
+}
+
+impl PageSegmentationMode {
+    fn to_tesseract(self) -> PageSegMode {
+        match self.0 {
+            1 => PageSegMode::PsmAutoOsd,
+            2 => PageSegMode::PsmAutoOnly,
+            3 => PageSegMode::PsmAuto,
+            4 => PageSegMode::PsmSingleColumn,
+            5 => PageSegMode::PsmSingleBlockVertText,
+            6 => PageSegMode::PsmSingleBlock,
+            7 => PageSegMode::PsmSingleLine,
+            8 => PageSegMode::PsmSingleWord,
+            9 => PageSegMode::PsmCircleWord,
+            10 => PageSegMode::PsmSingleChar,
+            11 => PageSegMode::PsmSparseText,
+            12 => PageSegMode::PsmSparseTextOsd,
+            13 => PageSegMode::PsmRawLine,
+            _ => unreachable!("validated in try_from"),
+        }
+    }
+}
+
+/// Tesseract OCR engine mode (`--oem`, 0-3)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(try_from = "u8")]
+pub struct EngineMode(u8);
+
+impl TryFrom<u8> for EngineMode {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0..=3 => Ok(Self(value)),
+            _ => Err(format!("Invalid OCR engine mode: {} (expected 0-3)", value)),
+        }
+    }
+}
+
+impl EngineMode {
+    fn to_tesseract(self) -> OcrEngineMode {
+        match self.0 {
+            0 => OcrEngineMode::TesseractOnly,
+            1 => OcrEngineMode::LstmOnly,
+            2 => OcrEngineMode::TesseractLstmCombined,
+            _ => OcrEngineMode::Default,
+        }
+    }
+}
+
+/// Valid range for `user_defined_dpi`, as enforced by Tesseract
+const MIN_USER_DPI: u32 = 70;
+const MAX_USER_DPI: u32 = 2400;
+
+/// Variables controlled by typed options; not accepted in `variables`
+const RESERVED_VARIABLES: [&str; 6] = [
+    "tessedit_pageseg_mode",
+    "tessedit_ocr_engine_mode",
+    "tessedit_char_whitelist",
+    "tessedit_char_blacklist",
+    "user_defined_dpi",
+    "preserve_interword_spaces",
+];
+
+/// Tesseract options; unset fields keep Tesseract's defaults
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TesseractOptions {
+    pub page_seg_mode: Option<PageSegmentationMode>,
+    pub engine_mode: Option<EngineMode>,
+    /// Only recognize these characters
+    pub char_whitelist: Option<String>,
+    /// Never recognize these characters
+    pub char_blacklist: Option<String>,
+    /// Source resolution hint for images without DPI metadata
+    pub user_defined_dpi: Option<u32>,
+    pub preserve_interword_spaces: Option<bool>,
+    /// Additional Tesseract variables, applied before the typed options
+    pub variables: HashMap<String, String>,
+}
+
+impl TesseractOptions {
+    /// Check option values before any work is done
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(dpi) = self.user_defined_dpi {
+            if !(MIN_USER_DPI..=MAX_USER_DPI).contains(&dpi) {
+                return Err(format!(
+                    "Invalid user-defined DPI: {} (expected {}-{})",
+                    dpi, MIN_USER_DPI, MAX_USER_DPI
+                ));
+            }
+        }
+
+        for chars in [&self.char_whitelist, &self.char_blacklist]
+            .into_iter()
+            .flatten()
+        {
+            if chars.contains('\0') {
+                return Err("Character lists must not contain NUL".to_string());
+            }
+        }
+
+        for (name, value) in &self.variables {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!("Invalid Tesseract variable name: {:?}", name));
+            }
+            if RESERVED_VARIABLES.contains(&name.as_str()) {
+                return Err(format!(
+                    "Tesseract variable {} must be set through its dedicated option",
+                    name
+                ));
+            }
+            if value.contains('\0') {
+                return Err(format!("Invalid value for Tesseract variable {}", name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Create a Tesseract instance configured with the given options
+///
+/// # Arguments
+/// * `datapath` - tessdata directory, or `None` for the system default
+/// * `language` - Tesseract language code
+/// * `options` - Validated engine options
+///
+/// # Returns
+/// A Tesseract instance ready for `set_image`
+pub fn create_tesseract(
+    datapath: Option<&str>,
+    language: &str,
+    options: &TesseractOptions,
+) -> Result<Tesseract, String> {
+    let tesseract = match options.engine_mode {
+        Some(mode) => Tesseract::new_with_oem(datapath, Some(language), mode.to_tesseract()),
+        None => Tesseract::new(datapath, Some(language)),
+    };
+    let mut tesseract = tesseract.map_err(|e| format!("Failed to initialize Tesseract: {}", e))?;
+
+    if let Some(mode) = options.page_seg_mode {
+        tesseract.set_page_seg_mode(mode.to_tesseract());
+    }
+
+    let mut variables: Vec<(&str, String)> = options
+        .variables
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect();
+    // Deterministic order keeps runs reproducible
+    variables.sort();
+
+    if let Some(chars) = &options.char_whitelist {
+        variables.push(("tessedit_char_whitelist", chars.clone()));
+    }
+    if let Some(chars) = &options.char_blacklist {
+        variables.push(("tessedit_char_blacklist", chars.clone()));
+    }
+    if let Some(dpi) = options.user_defined_dpi {
+        variables.push(("user_defined_dpi", dpi.to_string()));
+    }
+    if let Some(preserve) = options.preserve_interword_spaces {
+        variables.push(("preserve_interword_spaces", (preserve as u8).to_string()));
+    }
+
+    for (name, value) in variables {
+        tesseract = tesseract
+            .set_variable(name, &value)
+            .map_err(|e| format!("Failed to set Tesseract variable {}: {}", name, e))?;
+    }
+
+    Ok(tesseract)
+}
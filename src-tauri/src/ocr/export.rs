@@ -0,0 +1,383 @@
+//! Layout export formats
+//!
+//! Renders the OCR layout tree as hOCR, ALTO 4 XML or Tesseract-style TSV.
+//! Output is generated from `OcrBlock`s rather than Tesseract's own
+//! renderers so that all coordinates are in source image space.
+
+use serde::Deserialize;
+use std::fmt::Write;
+
+use super::layout::OcrBlock;
+use crate::preprocessing::BoundingBox;
+
+/// Output format for OCR results
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Plain text
+    #[default]
+    Text,
+    /// hOCR (XHTML with bounding box metadata)
+    Hocr,
+    /// ALTO 4 XML
+    Alto,
+    /// Tesseract-style tab-separated values
+    Tsv,
+}
+
+impl OutputFormat {
+    /// Conventional file extension for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "txt",
+            OutputFormat::Hocr => "hocr",
+            OutputFormat::Alto => "xml",
+            OutputFormat::Tsv => "tsv",
+        }
+    }
+}
+
+/// One page of layout to export
+pub struct LayoutPage<'a> {
+    pub image_path: &'a str,
+    pub width: u32,
+    pub height: u32,
+    pub text: &'a str,
+    pub blocks: &'a [OcrBlock],
+}
+
+/// Render pages in the requested format
+pub fn render_layout(format: OutputFormat, pages: &[LayoutPage]) -> String {
+    match format {
+        OutputFormat::Text => pages
+            .iter()
+            .map(|page| page.text)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputFormat::Hocr => render_hocr(pages),
+        OutputFormat::Alto => render_alto(pages),
+        OutputFormat::Tsv => render_tsv(pages),
+    }
+}
+
+/// Escape text for XML content and attribute values
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// hOCR `bbox x0 y0 x1 y1` property
+fn hocr_bbox(bbox: &BoundingBox) -> String {
+    format!(
+        "bbox {} {} {} {}",
+        bbox.left,
+        bbox.top,
+        bbox.right(),
+        bbox.bottom()
+    )
+}
+
+/// ALTO position attributes
+fn alto_position(bbox: &BoundingBox) -> String {
+    format!(
+        "HPOS=\"{}\" VPOS=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\"",
+        bbox.left, bbox.top, bbox.width, bbox.height
+    )
+}
+
+/// Render hOCR 1.2
+///
+/// Blocks map to `ocr_carea`, paragraphs to `ocr_par`, lines to
+/// `ocr_line` and words to `ocrx_word` with `x_wconf` confidences.
+fn render_hocr(pages: &[LayoutPage]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">\n");
+    out.push_str("<html xmlns=\"http://www.w3.org/1999/xhtml\" xml:lang=\"en\" lang=\"en\">\n");
+    out.push_str(" <head>\n  <title></title>\n");
+    out.push_str("  <meta http-equiv=\"Content-Type\" content=\"text/html;charset=utf-8\"/>\n");
+    let _ = writeln!(
+        out,
+        "  <meta name=\"ocr-system\" content=\"imagio {}\"/>",
+        env!("CARGO_PKG_VERSION")
+    );
+    out.push_str("  <meta name=\"ocr-capabilities\" content=\"ocr_page ocr_carea ocr_par ocr_line ocrx_word\"/>\n");
+    out.push_str(" </head>\n <body>\n");
+
+    for (page_index, page) in pages.iter().enumerate() {
+        let page_num = page_index + 1;
+        let _ = writeln!(
+            out,
+            "  <div class=\"ocr_page\" id=\"page_{}\" title=\"image &quot;{}&quot;; bbox 0 0 {} {}; ppageno {}\">",
+            page_num,
+            escape_xml(page.image_path),
+            page.width,
+            page.height,
+            page_index
+        );
+
+        let (mut block_id, mut par_id, mut line_id, mut word_id) = (0, 0, 0, 0);
+        for block in page.blocks {
+            block_id += 1;
+            let _ = writeln!(
+                out,
+                "   <div class=\"ocr_carea\" id=\"block_{}_{}\" title=\"{}\">",
+                page_num,
+                block_id,
+                hocr_bbox(&block.bbox)
+            );
+            for paragraph in &block.paragraphs {
+                par_id += 1;
+                let _ = writeln!(
+                    out,
+                    "    <p class=\"ocr_par\" id=\"par_{}_{}\" title=\"{}\">",
+                    page_num,
+                    par_id,
+                    hocr_bbox(&paragraph.bbox)
+                );
+                for line in &paragraph.lines {
+                    line_id += 1;
+                    let _ = writeln!(
+                        out,
+                        "     <span class=\"ocr_line\" id=\"line_{}_{}\" title=\"{}\">",
+                        page_num,
+                        line_id,
+                        hocr_bbox(&line.bbox)
+                    );
+                    for word in &line.words {
+                        word_id += 1;
+                        let _ = writeln!(
+                            out,
+                            "      <span class=\"ocrx_word\" id=\"word_{}_{}\" title=\"{}; x_wconf {}\">{}</span>",
+                            page_num,
+                            word_id,
+                            hocr_bbox(&word.bbox),
+                            word.confidence.round() as i32,
+                            escape_xml(&word.text)
+                        );
+                    }
+                    out.push_str("     </span>\n");
+                }
+                out.push_str("    </p>\n");
+            }
+            out.push_str("   </div>\n");
+        }
+        out.push_str("  </div>\n");
+    }
+
+    out.push_str(" </body>\n</html>\n");
+    out
+}
+
+/// Render ALTO 4 XML
+///
+/// Follows Tesseract's mapping: blocks become `ComposedBlock`s and
+/// paragraphs become `TextBlock`s, since ALTO has no paragraph level.
+fn render_alto(pages: &[LayoutPage]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<alto xmlns=\"http://www.loc.gov/standards/alto/ns-v4#\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:schemaLocation=\"http://www.loc.gov/standards/alto/ns-v4# http://www.loc.gov/alto/v4/alto-4-0.xsd\">\n");
+    out.push_str(" <Description>\n  <MeasurementUnit>pixel</MeasurementUnit>\n");
+    // ALTO allows one file name per document; pages of a multi-page input
+    // share their source file
+    if let Some(page) = pages.first() {
+        out.push_str("  <sourceImageInformation>\n");
+        let _ = writeln!(
+            out,
+            "   <fileName>{}</fileName>",
+            escape_xml(page.image_path)
+        );
+        out.push_str("  </sourceImageInformation>\n");
+    }
+    out.push_str(
+        "  <OCRProcessing ID=\"OCR_0\">\n   <ocrProcessingStep>\n    <processingSoftware>\n",
+    );
+    out.push_str("     <softwareName>Imagio</softwareName>\n");
+    let _ = writeln!(
+        out,
+        "     <softwareVersion>{}</softwareVersion>",
+        env!("CARGO_PKG_VERSION")
+    );
+    out.push_str("    </processingSoftware>\n   </ocrProcessingStep>\n  </OCRProcessing>\n");
+    out.push_str(" </Description>\n <Layout>\n");
+
+    for (page_index, page) in pages.iter().enumerate() {
+        let page_box = BoundingBox {
+            left: 0,
+            top: 0,
+            width: page.width,
+            height: page.height,
+        };
+        let _ = writeln!(
+            out,
+            "  <Page WIDTH=\"{}\" HEIGHT=\"{}\" PHYSICAL_IMG_NR=\"{}\" ID=\"page_{}\">",
+            page.width,
+            page.height,
+            page_index + 1,
+            page_index
+        );
+        let _ = writeln!(out, "   <PrintSpace {}>", alto_position(&page_box));
+
+        let (mut block_id, mut par_id, mut line_id, mut word_id) = (0, 0, 0, 0);
+        for block in page.blocks {
+            let _ = writeln!(
+                out,
+                "    <ComposedBlock ID=\"cblock_{}_{}\" {}>",
+                page_index,
+                block_id,
+                alto_position(&block.bbox)
+            );
+            block_id += 1;
+            for paragraph in &block.paragraphs {
+                let _ = writeln!(
+                    out,
+                    "     <TextBlock ID=\"block_{}_{}\" {}>",
+                    page_index,
+                    par_id,
+                    alto_position(&paragraph.bbox)
+                );
+                par_id += 1;
+                for line in &paragraph.lines {
+                    let _ = writeln!(
+                        out,
+                        "      <TextLine ID=\"line_{}_{}\" {}>",
+                        page_index,
+                        line_id,
+                        alto_position(&line.bbox)
+                    );
+                    line_id += 1;
+                    for (i, word) in line.words.iter().enumerate() {
+                        if i > 0 {
+                            let prev = &line.words[i - 1].bbox;
+                            let _ = writeln!(
+                                out,
+                                "       <SP WIDTH=\"{}\" VPOS=\"{}\" HPOS=\"{}\"/>",
+                                word.bbox.left.saturating_sub(prev.right()),
+                                prev.top,
+                                prev.right()
+                            );
+                        }
+                        let _ = writeln!(
+                            out,
+                            "       <String ID=\"string_{}_{}\" {} WC=\"{:.2}\" CONTENT=\"{}\"/>",
+                            page_index,
+                            word_id,
+                            alto_position(&word.bbox),
+                            word.confidence / 100.0,
+                            escape_xml(&word.text)
+                        );
+                        word_id += 1;
+                    }
+                    out.push_str("      </TextLine>\n");
+                }
+                out.push_str("     </TextBlock>\n");
+            }
+            out.push_str("    </ComposedBlock>\n");
+        }
+        out.push_str("   </PrintSpace>\n  </Page>\n");
+    }
+
+    out.push_str(" </Layout>\n</alto>\n");
+    out
+}
+
+/// Append one TSV row
+fn tsv_row(
+    out: &mut String,
+    level: u32,
+    ids: [usize; 5],
+    bbox: &BoundingBox,
+    conf: f32,
+    text: &str,
+) {
+    let _ = writeln!(
+        out,
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        level,
+        ids[0],
+        ids[1],
+        ids[2],
+        ids[3],
+        ids[4],
+        bbox.left,
+        bbox.top,
+        bbox.width,
+        bbox.height,
+        conf,
+        text
+    );
+}
+
+/// Render Tesseract-style TSV
+///
+/// Same columns as `tesseract ... tsv`; containers carry a confidence
+/// of -1 and words their recognition confidence.
+fn render_tsv(pages: &[LayoutPage]) -> String {
+    let mut out = String::from(
+        "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n",
+    );
+
+    for (page_index, page) in pages.iter().enumerate() {
+        let page_num = page_index + 1;
+        let page_box = BoundingBox {
+            left: 0,
+            top: 0,
+            width: page.width,
+            height: page.height,
+        };
+        tsv_row(&mut out, 1, [page_num, 0, 0, 0, 0], &page_box, -1.0, "");
+
+        for (b, block) in page.blocks.iter().enumerate() {
+            tsv_row(
+                &mut out,
+                2,
+                [page_num, b + 1, 0, 0, 0],
+                &block.bbox,
+                -1.0,
+                "",
+            );
+            for (p, paragraph) in block.paragraphs.iter().enumerate() {
+                tsv_row(
+                    &mut out,
+                    3,
+                    [page_num, b + 1, p + 1, 0, 0],
+                    &paragraph.bbox,
+                    -1.0,
+                    "",
+                );
+                for (l, line) in paragraph.lines.iter().enumerate() {
+                    tsv_row(
+                        &mut out,
+                        4,
+                        [page_num, b + 1, p + 1, l + 1, 0],
+                        &line.bbox,
+                        -1.0,
+                        "",
+                    );
+                    for (w, word) in line.words.iter().enumerate() {
+                        tsv_row(
+                            &mut out,
+                            5,
+                            [page_num, b + 1, p + 1, l + 1, w + 1],
+                            &word.bbox,
+                            word.confidence,
+                            &word.text,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
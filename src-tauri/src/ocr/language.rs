@@ -0,0 +1,232 @@
+//! OCR language selection
+//!
+//! Handles Tesseract language specifications (`eng`, `eng+chi_sim`), the
+//! "auto" mode that picks languages from the script detected by OSD, and
+//! locating a tessdata directory that holds every requested model.
+
+use image::DynamicImage;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::osd::{detect_orientation_script, OsdResult};
+
+/// Language value that requests script detection
+pub const AUTO_LANGUAGE: &str = "auto";
+
+/// Language used when none is given or detection is inconclusive
+pub const DEFAULT_LANGUAGE: &str = "eng";
+
+/// Models that only ship with the app, never with system Tesseract
+const BUNDLED_ONLY_LANGUAGES: [&str; 1] = ["eng_degraded"];
+
+/// Minimum OSD script confidence to trust the detected script
+const MIN_SCRIPT_CONFIDENCE: f32 = 1.0;
+
+/// Well-known tessdata locations of system Tesseract installs
+const SYSTEM_TESSDATA_DIRS: [&str; 6] = [
+    "/opt/homebrew/share/tessdata",
+    "/usr/local/share/tessdata",
+    "/usr/share/tesseract-ocr/5/tessdata",
+    "/usr/share/tesseract-ocr/4.00/tessdata",
+    "/usr/share/tessdata",
+    "C:\\Program Files\\Tesseract-OCR\\tessdata",
+];
+
+/// Script detected by OSD
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptDetection {
+    /// Tesseract script name, e.g. "Latin", "Han"
+    pub script: String,
+    pub confidence: f32,
+}
+
+/// Split a language specification into its model names
+///
+/// # Arguments
+/// * `language` - Tesseract language string such as `eng+chi_sim`
+///
+/// # Returns
+/// The model names, or an error for empty or malformed entries
+pub fn parse_languages(language: &str) -> Result<Vec<&str>, String> {
+    let models: Vec<&str> = language.split('+').map(str::trim).collect();
+    for model in &models {
+        if *model == AUTO_LANGUAGE {
+            return Err("\"auto\" cannot be combined with other languages".to_string());
+        }
+        if model.is_empty()
+            || !model
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!("Invalid language: {:?}", language));
+        }
+    }
+    Ok(models)
+}
+
+/// Candidate models for a Tesseract OSD script name, most specific first
+fn models_for_script(script: &str) -> &'static [&'static str] {
+    match script {
+        "Latin" => &["eng"],
+        "Han" => &["chi_sim", "chi_tra", "jpn"],
+        "Japanese" | "Hiragana" | "Katakana" => &["jpn"],
+        "Hangul" => &["kor"],
+        "Cyrillic" => &["rus", "ukr"],
+        "Arabic" => &["ara"],
+        "Greek" => &["ell"],
+        "Hebrew" => &["heb"],
+        "Devanagari" => &["hin"],
+        "Thai" => &["tha"],
+        _ => &[],
+    }
+}
+
+/// Locates tessdata directories holding the requested models
+pub struct TessdataLocator {
+    bundled_dirs: Vec<PathBuf>,
+    system_dirs: Vec<PathBuf>,
+}
+
+impl TessdataLocator {
+    /// Create a locator searching `bundled_dirs` before system locations
+    pub fn new(bundled_dirs: Vec<PathBuf>) -> Self {
+        let mut system_dirs = Vec::new();
+        if let Some(prefix) = std::env::var_os("TESSDATA_PREFIX") {
+            let prefix = PathBuf::from(prefix);
+            system_dirs.push(prefix.join("tessdata"));
+            system_dirs.push(prefix);
+        }
+        system_dirs.extend(SYSTEM_TESSDATA_DIRS.iter().map(PathBuf::from));
+
+        Self {
+            bundled_dirs,
+            system_dirs,
+        }
+    }
+
+    fn has_model(dir: &Path, model: &str) -> bool {
+        dir.join(format!("{}.traineddata", model)).is_file()
+    }
+
+    /// Whether a model is available in any known directory
+    pub fn is_installed(&self, model: &str) -> bool {
+        self.bundled_dirs
+            .iter()
+            .chain(&self.system_dirs)
+            .any(|dir| Self::has_model(dir, model))
+    }
+
+    /// Find the tessdata directory to pass to Tesseract for `language`
+    ///
+    /// # Returns
+    /// * `Some(dir)` - a directory containing every requested model
+    /// * `None` - let Tesseract use its built-in default location
+    pub fn resolve(&self, language: &str) -> Result<Option<String>, String> {
+        let models = parse_languages(language)?;
+
+        if let Some(dir) = self
+            .bundled_dirs
+            .iter()
+            .chain(&self.system_dirs)
+            .find(|dir| models.iter().all(|model| Self::has_model(dir, model)))
+        {
+            return Ok(Some(dir.to_string_lossy().to_string()));
+        }
+
+        if let Some(model) = models
+            .iter()
+            .find(|model| BUNDLED_ONLY_LANGUAGES.contains(model))
+        {
+            return Err(format!(
+                "Bundled OCR model was not found: {}.traineddata",
+                model
+            ));
+        }
+
+        Ok(None)
+    }
+
+    /// Choose installed models for a detected script
+    ///
+    /// English is added as a secondary language for non-Latin scripts
+    /// since mixed screenshots commonly contain Latin text.
+    ///
+    /// # Returns
+    /// A Tesseract language string, falling back to English when no
+    /// model for the script is installed
+    pub fn language_for_script(&self, detection: &ScriptDetection) -> String {
+        if detection.confidence < MIN_SCRIPT_CONFIDENCE {
+            return DEFAULT_LANGUAGE.to_string();
+        }
+
+        let Some(model) = models_for_script(&detection.script)
+            .iter()
+            .find(|model| self.is_installed(model))
+        else {
+            return DEFAULT_LANGUAGE.to_string();
+        };
+
+        if *model != DEFAULT_LANGUAGE && self.is_installed(DEFAULT_LANGUAGE) {
+            format!("{}+{}", model, DEFAULT_LANGUAGE)
+        } else {
+            model.to_string()
+        }
+    }
+}
+
+/// Determine the language to recognize a page with
+///
+/// In auto mode, uses OSD on the preprocessed image (or the result from
+/// orientation detection, when available) and picks installed models for
+/// the detected script; detection failures fall back to English.
+///
+/// # Arguments
+/// * `img` - Preprocessed page image
+/// * `requested` - Language from the processing parameters
+/// * `tessdata` - Locator for installed models
+/// * `osd` - OSD result already computed for this page, if any
+///
+/// # Returns
+/// The Tesseract language string and, in auto mode, the detected script
+pub fn select_language(
+    img: &DynamicImage,
+    requested: &str,
+    tessdata: &TessdataLocator,
+    osd: Option<&OsdResult>,
+) -> (String, Option<ScriptDetection>) {
+    if requested.is_empty() {
+        return (DEFAULT_LANGUAGE.to_string(), None);
+    }
+    if requested != AUTO_LANGUAGE {
+        return (requested.to_string(), None);
+    }
+
+    let osd = match osd {
+        Some(osd) => Ok(osd.clone()),
+        None => {
+            let osd_path = tessdata.resolve("osd").ok().flatten();
+            detect_orientation_script(&img.to_luma8(), osd_path.as_deref())
+        }
+    };
+    match osd {
+        Ok(osd) => {
+            log::info!(
+                "[OSD] Script: {} ({:.2})",
+                osd.script,
+                osd.script_confidence
+            );
+            let detection = ScriptDetection {
+                script: osd.script,
+                confidence: osd.script_confidence,
+            };
+            let language = tessdata.language_for_script(&detection);
+            log::info!("[OSD] Using language: {}", language);
+            (language, Some(detection))
+        }
+        Err(e) => {
+            log::warn!("[OSD] {}; falling back to {}", e, DEFAULT_LANGUAGE);
+            (DEFAULT_LANGUAGE.to_string(), None)
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Structured OCR layout
+//!
+//! Builds a block → paragraph → line → word tree from Tesseract's TSV
+//! output, with bounding boxes mapped back to source image coordinates.
+
+use serde::Serialize;
+
+use crate::preprocessing::{BoundingBox, CoordinateMapper};
+
+/// A recognized word
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrWord {
+    pub text: String,
+    /// Tesseract confidence (0-100)
+    pub confidence: f32,
+    pub bbox: BoundingBox,
+}
+
+/// A line of text
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrLine {
+    pub text: String,
+    /// Mean confidence of the words in the line (0-100)
+    pub confidence: f32,
+    pub bbox: BoundingBox,
+    pub words: Vec<OcrWord>,
+}
+
+/// A paragraph of text lines
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrParagraph {
+    pub bbox: BoundingBox,
+    pub lines: Vec<OcrLine>,
+}
+
+/// A text block (column, caption, etc.)
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrBlock {
+    pub bbox: BoundingBox,
+    pub paragraphs: Vec<OcrParagraph>,
+}
+
+/// Tesseract TSV hierarchy levels
+const LEVEL_BLOCK: u32 = 2;
+const LEVEL_PARAGRAPH: u32 = 3;
+const LEVEL_LINE: u32 = 4;
+const LEVEL_WORD: u32 = 5;
+
+/// Parse Tesseract TSV output into a layout tree
+///
+/// TSV columns: level, page_num, block_num, par_num, line_num, word_num,
+/// left, top, width, height, conf, text. Rows arrive in reading order with
+/// each container preceding its children. Empty words and containers
+/// without any words are dropped.
+///
+/// # Arguments
+/// * `tsv` - Output of `Tesseract::get_tsv_text`
+/// * `mapper` - Maps processed-image coordinates back to the source image
+///
+/// # Returns
+/// The text blocks in reading order
+pub fn parse_tsv(tsv: &str, mapper: &CoordinateMapper) -> Vec<OcrBlock> {
+    let mut blocks: Vec<OcrBlock> = Vec::new();
+
+    for row in tsv.lines() {
+        let fields: Vec<&str> = row.splitn(12, '\t').collect();
+        if fields.len() < 11 {
+            continue;
+        }
+
+        // Skips the header row if present
+        let Ok(level) = fields[0].parse::<u32>() else {
+            continue;
+        };
+        let coords: Vec<u32> = fields[6..10]
+            .iter()
+            .filter_map(|v| v.trim().parse::<i64>().ok())
+            .map(|v| v.max(0) as u32)
+            .collect();
+        if coords.len() != 4 {
+            continue;
+        }
+        let bbox = mapper.map_box(BoundingBox {
+            left: coords[0],
+            top: coords[1],
+            width: coords[2],
+            height: coords[3],
+        });
+
+        match level {
+            LEVEL_BLOCK => blocks.push(OcrBlock {
+                bbox,
+                paragraphs: Vec::new(),
+            }),
+            LEVEL_PARAGRAPH => {
+                if let Some(block) = blocks.last_mut() {
+                    block.paragraphs.push(OcrParagraph {
+                        bbox,
+                        lines: Vec::new(),
+                    });
+                }
+            }
+            LEVEL_LINE => {
+                if let Some(paragraph) = blocks
+                    .last_mut()
+                    .and_then(|block| block.paragraphs.last_mut())
+                {
+                    paragraph.lines.push(OcrLine {
+                        text: String::new(),
+                        confidence: 0.0,
+                        bbox,
+                        words: Vec::new(),
+                    });
+                }
+            }
+            LEVEL_WORD => {
+                let text = fields.get(11).map(|t| t.trim()).unwrap_or("");
+                if text.is_empty() {
+                    continue;
+                }
+                let confidence = fields[10].trim().parse::<f32>().unwrap_or(0.0).max(0.0);
+                if let Some(line) = blocks
+                    .last_mut()
+                    .and_then(|block| block.paragraphs.last_mut())
+                    .and_then(|paragraph| paragraph.lines.last_mut())
+                {
+                    line.words.push(OcrWord {
+                        text: text.to_string(),
+                        confidence,
+                        bbox,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Fill in line summaries and drop empty containers
+    for block in &mut blocks {
+        for paragraph in &mut block.paragraphs {
+            paragraph.lines.retain(|line| !line.words.is_empty());
+            for line in &mut paragraph.lines {
+                line.text = line
+                    .words
+                    .iter()
+                    .map(|w| w.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                line.confidence =
+                    line.words.iter().map(|w| w.confidence).sum::<f32>() / line.words.len() as f32;
+            }
+        }
+        block.paragraphs.retain(|p| !p.lines.is_empty());
+    }
+    blocks.retain(|b| !b.paragraphs.is_empty());
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessing::{rescale, ResampleFilter};
+    use image::DynamicImage;
+
+    const HEADER: &str =
+        "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext";
+
+    fn row(level: u32, bbox: (u32, u32, u32, u32), conf: &str, text: &str) -> String {
+        format!(
+            "{}\t1\t1\t1\t1\t1\t{}\t{}\t{}\t{}\t{}\t{}",
+            level, bbox.0, bbox.1, bbox.2, bbox.3, conf, text
+        )
+    }
+
+    fn bbox(left: u32, top: u32, width: u32, height: u32) -> BoundingBox {
+        BoundingBox {
+            left,
+            top,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn parses_all_levels_into_a_tree() {
+        let tsv = [
+            HEADER.to_string(),
+            row(1, (0, 0, 400, 300), "-1", ""),
+            row(2, (10, 10, 200, 60), "-1", ""),
+            row(3, (10, 10, 200, 60), "-1", ""),
+            row(4, (10, 10, 200, 20), "-1", ""),
+            row(5, (10, 10, 80, 20), "96.5", "Hello"),
+            row(5, (100, 10, 110, 20), "91.5", "world"),
+            row(4, (10, 40, 100, 20), "-1", ""),
+            row(5, (10, 40, 100, 20), "88", "again"),
+            row(2, (10, 200, 100, 20), "-1", ""),
+            row(3, (10, 200, 100, 20), "-1", ""),
+            row(4, (10, 200, 100, 20), "-1", ""),
+            row(5, (10, 200, 100, 20), "70", "second"),
+        ]
+        .join("\n");
+
+        let blocks = parse_tsv(&tsv, &CoordinateMapper::new(400, 300));
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].bbox, bbox(10, 10, 200, 60));
+        assert_eq!(blocks[0].paragraphs.len(), 1);
+
+        let lines = &blocks[0].paragraphs[0].lines;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].text, "Hello world");
+        assert!((lines[0].confidence - 94.0).abs() < 1e-4);
+        assert_eq!(lines[0].words[1].bbox, bbox(100, 10, 110, 20));
+        assert_eq!(lines[1].text, "again");
+        assert_eq!(blocks[1].paragraphs[0].lines[0].text, "second");
+    }
+
+    #[test]
+    fn drops_empty_words_and_containers() {
+        let tsv = [
+            row(2, (0, 0, 100, 100), "-1", ""),
+            row(3, (0, 0, 100, 100), "-1", ""),
+            row(4, (0, 0, 100, 20), "-1", ""),
+            row(5, (0, 0, 10, 20), "95", " "),
+            row(2, (0, 50, 100, 20), "-1", ""),
+            row(3, (0, 50, 100, 20), "-1", ""),
+            row(4, (0, 50, 100, 20), "-1", ""),
+            row(5, (0, 50, 40, 20), "-1", "kept"),
+        ]
+        .join("\n");
+
+        let blocks = parse_tsv(&tsv, &CoordinateMapper::new(100, 100));
+        assert_eq!(blocks.len(), 1);
+        let word = &blocks[0].paragraphs[0].lines[0].words[0];
+        assert_eq!(word.text, "kept");
+        // Negative confidences are clamped to zero
+        assert_eq!(word.confidence, 0.0);
+    }
+
+    #[test]
+    fn skips_malformed_rows() {
+        let tsv = [
+            "5\t1\t1\t1\t1\t1\t0\t0".to_string(),
+            row(2, (0, 0, 50, 20), "-1", ""),
+            "3\t1\t1\t1\t0\t0\tx\t0\t50\t20\t-1\t".to_string(),
+            row(3, (0, 0, 50, 20), "-1", ""),
+            row(4, (0, 0, 50, 20), "-1", ""),
+            row(5, (0, 0, 50, 20), "80", "ok"),
+        ]
+        .join("\n");
+
+        let blocks = parse_tsv(&tsv, &CoordinateMapper::new(100, 100));
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].paragraphs.len(), 1);
+        assert_eq!(blocks[0].paragraphs[0].lines[0].text, "ok");
+    }
+
+    #[test]
+    fn maps_boxes_to_source_coordinates() {
+        let tsv = [
+            row(2, (0, 0, 100, 40), "-1", ""),
+            row(3, (0, 0, 100, 40), "-1", ""),
+            row(4, (0, 0, 100, 40), "-1", ""),
+            row(5, (20, 10, 40, 20), "90", "scaled"),
+        ]
+        .join("\n");
+        // Processed at twice the source resolution
+        let (_, transform) = rescale(
+            &DynamicImage::new_luma8(200, 100),
+            2.0,
+            ResampleFilter::Bicubic,
+        );
+        let mut mapper = CoordinateMapper::new(200, 100);
+        mapper.extend(transform);
+
+        let blocks = parse_tsv(&tsv, &mapper);
+        let word = &blocks[0].paragraphs[0].lines[0].words[0];
+        assert_eq!(word.bbox, bbox(10, 5, 20, 10));
+    }
+}
@@ -1,226 +1,284 @@
 //! OCR processing module
 //!
 //! Provides the main OCR pipeline including:
-//! - Image preprocessing
+//! - Image preprocessing through a configurable pipeline
 //! - Adaptive preprocessing based on quality metrics
-//! - Tesseract OCR integration
+//! - Tesseract OCR integration and engine options
+//! - Best-of mode over candidate pipelines
+//! - Multi-language and automatic script detection
+//! - Page orientation detection
+//! - Structured layout (blocks, paragraphs, lines, words)
+//! - hOCR, ALTO and TSV export
+//! - Structured trace of timings and decisions
+
+mod best_of;
+mod engine;
+mod export;
+mod language;
+mod layout;
+mod orientation;
+mod osd;
+mod trace;
+
+pub use best_of::{recognize_best, BestOfResult};
+pub use engine::{create_tesseract, PageSegmentationMode, TesseractOptions};
+pub use export::{escape_xml, render_layout, LayoutPage, OutputFormat};
+pub use language::{select_language, ScriptDetection, TessdataLocator, AUTO_LANGUAGE};
+pub use layout::{parse_tsv, OcrBlock, OcrWord};
+pub use orientation::{detect_page_orientation, OrientationDetection};
+pub use osd::OsdResult;
+pub use trace::{Trace, TraceEvent};
 
 use image::DynamicImage;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
-use crate::binarization::{
-    apply_adaptive_threshold, apply_clahe, apply_mean_threshold, apply_otsu_threshold,
-    apply_sauvola_threshold,
-};
-use crate::morphology::{apply_closing, apply_dilation, apply_erosion, apply_opening};
+use crate::binarization::{BinarizationMethod, DEFAULT_CLAHE_CLIP_LIMIT, DEFAULT_CLAHE_TILE_GRID};
+use crate::input::ExifOrientation;
+use crate::morphology::MorphologyOperation;
+use crate::pipeline::{Pipeline, StageOutput, StepContext};
 use crate::preprocessing::{
-    adjust_brightness, adjust_contrast, adjust_sharpness, apply_bilateral_filter,
-    apply_gaussian_blur, correct_skew, correct_skew_projection, remove_borders,
+    measure_illumination_unevenness, normalize_polarity, CoordinateMapper, DewarpReport, Polarity,
+    SkewMethod, TableGrid, UNEVEN_ILLUMINATION_THRESHOLD,
 };
 use crate::quality::{assess_image_quality, ImageQualityMetrics};
+use crate::table::Table;
 
 /// Image processing parameters for OCR
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessingParams {
     pub contrast: f32,
     pub brightness: f32,
     pub sharpness: f32,
-    pub binarization_method: String,
+    pub binarization_method: BinarizationMethod,
+    /// Local threshold window in pixels (default depends on the method)
+    #[serde(default)]
+    pub threshold_window: Option<u32>,
+    /// Local threshold sensitivity `k` (default depends on the method)
+    #[serde(default)]
+    pub threshold_k: Option<f32>,
+    /// Sauvola dynamic range `R` (default 128)
+    #[serde(default)]
+    pub threshold_r: Option<f32>,
     pub use_clahe: bool,
+    /// CLAHE tiles per side (default 8)
+    #[serde(default = "default_clahe_tile_grid")]
+    pub clahe_tile_grid: u32,
+    /// CLAHE clip limit relative to the mean histogram bin (default 2.0)
+    #[serde(default = "default_clahe_clip_limit")]
+    pub clahe_clip_limit: f32,
     pub gaussian_blur: f32,
     pub bilateral_filter: bool,
-    pub morphology: String,
+    pub morphology: MorphologyOperation,
     pub language: String,
     pub correct_skew: bool,
-    pub skew_method: String,
+    pub skew_method: SkewMethod,
+    /// Detect a photographed page and rectify it
+    #[serde(default)]
+    pub correct_perspective: bool,
+    /// Page corners as `[x, y]` in source image pixels, in the order
+    /// top-left, top-right, bottom-right, bottom-left; enables perspective
+    /// correction with these corners instead of detected ones
+    #[serde(default)]
+    pub document_corners: Option<[[f32; 2]; 4]>,
+    /// Straighten text lines curved by page curl (book photos)
+    #[serde(default)]
+    pub dewarp: bool,
     pub remove_borders: bool,
     pub adaptive_mode: bool,
+    /// Detect and fix pages rotated by 90°, 180° or 270°
+    #[serde(default)]
+    pub detect_orientation: bool,
+    /// Resample so the dominant text height matches `target_text_height`
+    #[serde(default)]
+    pub normalize_scale: bool,
+    /// Target text height in pixels for scale normalization (default 24)
+    #[serde(default)]
+    pub target_text_height: Option<f32>,
+    /// Invert light-on-dark regions so text is dark on light
+    #[serde(default)]
+    pub normalize_polarity: bool,
+    /// Fade out colors other than the dominant ink (stamps, highlighter)
+    #[serde(default)]
+    pub color_dropout: bool,
+    /// Flatten shadows and lighting gradients before thresholding
+    #[serde(default)]
+    pub normalize_background: bool,
+    /// Erase table and form grid lines
+    #[serde(default)]
+    pub remove_lines: bool,
+    /// Return the erased grid lines with the result
+    #[serde(default)]
+    pub return_table_grid: bool,
+    /// Recognize tables from the word boxes and ruled lines
+    #[serde(default)]
+    pub detect_tables: bool,
+    /// Remove specks and pinholes after binarization
+    #[serde(default)]
+    pub despeckle: bool,
+    /// Also remove blobs touching the page border when despeckling
+    #[serde(default)]
+    pub despeckle_border: bool,
+    /// Rasterization resolution for PDF input (default 300)
+    #[serde(default)]
+    pub pdf_dpi: Option<f32>,
+    /// Page segmentation, engine mode and other Tesseract settings
+    #[serde(default)]
+    pub tesseract: TesseractOptions,
+    /// Explicit step order; replaces the pipeline derived from the fields
+    /// above, which adaptive mode then no longer tunes
+    #[serde(default)]
+    pub pipeline: Option<Pipeline>,
+    /// Return a thumbnail, timing and parameters for every pipeline stage
+    #[serde(default)]
+    pub debug: bool,
+    /// Run candidate pipelines and keep the most confident result
+    #[serde(default)]
+    pub best_of: bool,
 }
 
-/// OCR result containing extracted text and metadata
+impl ProcessingParams {
+    /// Check parameter ranges before any work is done
+    ///
+    /// Unknown method names are already rejected during deserialization.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(dpi) = self.pdf_dpi {
+            if !(dpi > 0.0 && dpi <= 1200.0) {
+                return Err(format!("Invalid PDF DPI: {} (expected up to 1200)", dpi));
+            }
+        }
+        self.tesseract.validate()?;
+        match &self.pipeline {
+            Some(pipeline) => pipeline.validate(),
+            None => Pipeline::from_params(self).validate(),
+        }
+    }
+}
+
+fn default_clahe_tile_grid() -> u32 {
+    DEFAULT_CLAHE_TILE_GRID
+}
+
+fn default_clahe_clip_limit() -> f32 {
+    DEFAULT_CLAHE_CLIP_LIMIT
+}
+
+/// OCR result for a single page of the input
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct OcrResult {
+pub struct OcrPageResult {
+    /// 1-based page (or frame) number
+    pub page_number: u32,
     pub text: String,
     pub processed_image_path: String,
     pub quality_metrics: Option<ImageQualityMetrics>,
+    /// EXIF orientation applied on load; boxes are relative to the
+    /// oriented image
+    pub exif_orientation: Option<ExifOrientation>,
+    /// Tesseract language string used for recognition
+    pub language: String,
+    /// Script detected in auto language mode
+    pub script: Option<ScriptDetection>,
+    /// Orientation detected before deskewing, if enabled
+    pub orientation: Option<OrientationDetection>,
+    /// Layout tree with boxes in source image coordinates
+    pub blocks: Vec<OcrBlock>,
+    /// Source image dimensions, after EXIF orientation
+    pub image_width: u32,
+    pub image_height: u32,
+    /// Resampling factor from scale normalization (1.0 when unchanged);
+    /// the processed image is at this scale relative to the source
+    pub scale_factor: f32,
+    /// Curvature removed by dewarping, if it ran and found curved lines
+    pub dewarp: Option<DewarpReport>,
+    /// Ruled lines erased by line removal, when requested
+    pub table_grid: Option<TableGrid>,
+    /// Tables recognized on the page, when table detection is enabled
+    pub tables: Vec<Table>,
+    /// Output of each preprocessing stage in debug mode, in run order
+    pub stages: Vec<PipelineStage>,
+    /// Candidate scores in best-of mode
+    pub best_of: Option<BestOfResult>,
+}
+
+/// Output of one preprocessing stage, returned in debug mode
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStage {
+    pub name: String,
+    /// Parameters the stage ran with
+    pub params: serde_json::Value,
+    pub duration_ms: u64,
+    /// Downscaled PNG of the stage output
+    pub thumbnail_path: String,
+}
+
+/// OCR result containing extracted text and metadata
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrResult {
+    /// Combined text of all pages
+    pub text: String,
+    /// Per-page results in page order
+    pub pages: Vec<OcrPageResult>,
+    /// Result rendered in the requested structured output format
+    pub formatted_output: Option<String>,
+    /// Step timings and automatic decisions, in order
+    pub trace: Vec<TraceEvent>,
+}
+
+/// Output of the preprocessing pipeline
+pub struct PreprocessedImage {
+    pub image: DynamicImage,
+    /// Maps coordinates on `image` back to the input image
+    pub mapper: CoordinateMapper,
+    /// Orientation detected before deskewing, if enabled
+    pub orientation: Option<OrientationDetection>,
+    /// OSD result from orientation detection, reused for script detection
+    pub osd: Option<OsdResult>,
+    /// Combined resampling factor applied by scale normalization
+    pub scale_factor: f32,
+    /// Curvature removed by dewarping, if it ran and found curved lines
+    pub dewarp: Option<DewarpReport>,
+    /// Ruled lines found by line removal, when requested
+    pub table_grid: Option<TableGrid>,
+    /// Per-stage outputs when `params.debug` is set
+    pub stages: Vec<StageOutput>,
 }
 
 /// Apply image preprocessing based on parameters
 ///
-/// Follows best practices for OCR preprocessing:
-/// 1. Border removal (if enabled)
-/// 2. Geometric correction (deskewing)
-/// 3. Noise reduction (Gaussian blur or bilateral filter)
-/// 4. Brightness/Contrast adjustment
-/// 5. Sharpening
-/// 6. Contrast enhancement (CLAHE)
-/// 7. Morphological operations
-/// 8. Binarization (always last)
+/// Runs `params.pipeline` when given; otherwise the default pipeline
+/// built from the individual parameters (see [`Pipeline::from_params`]).
 pub fn preprocess_image(
     img: DynamicImage,
     params: &ProcessingParams,
-) -> Result<DynamicImage, String> {
-    let mut processed = img;
-
-    // Step 1: Border removal
-    if params.remove_borders {
-        let start = Instant::now();
-        processed = remove_borders(&processed);
-        println!(
-            "[Performance]   - Border removal: {}ms",
-            start.elapsed().as_millis()
-        );
-    }
-
-    // Step 2: Deskew
-    if params.correct_skew {
-        let start = Instant::now();
-        processed = if params.skew_method == "projection" {
-            correct_skew_projection(&processed)?
-        } else {
-            correct_skew(&processed)?
-        };
-        println!(
-            "[Performance]   - Skew correction ({}): {}ms",
-            params.skew_method,
-            start.elapsed().as_millis()
-        );
-    }
-
-    // Step 3: Noise reduction
-    if params.bilateral_filter {
-        let start = Instant::now();
-        processed = apply_bilateral_filter(&processed);
-        println!(
-            "[Performance]   - Bilateral filter: {}ms",
-            start.elapsed().as_millis()
-        );
-    } else if params.gaussian_blur > 0.0 {
-        let start = Instant::now();
-        processed = apply_gaussian_blur(&processed, params.gaussian_blur);
-        println!(
-            "[Performance]   - Gaussian blur: {}ms",
-            start.elapsed().as_millis()
-        );
-    }
-
-    // Step 4: Brightness and contrast adjustment
-    if params.brightness != 0.0 {
-        let start = Instant::now();
-        processed = adjust_brightness(&processed, params.brightness);
-        println!(
-            "[Performance]   - Brightness: {}ms",
-            start.elapsed().as_millis()
-        );
-    }
-
-    if params.contrast != 1.0 {
-        let start = Instant::now();
-        processed = adjust_contrast(&processed, params.contrast);
-        println!(
-            "[Performance]   - Contrast: {}ms",
-            start.elapsed().as_millis()
-        );
-    }
-
-    // Step 5: Sharpening
-    if params.sharpness > 1.0 {
-        let start = Instant::now();
-        processed = adjust_sharpness(&processed, params.sharpness);
-        println!(
-            "[Performance]   - Sharpness: {}ms",
-            start.elapsed().as_millis()
-        );
-    }
-
-    // Step 6: CLAHE
-    if params.use_clahe {
-        let start = Instant::now();
-        processed = apply_clahe(&processed)?;
-        println!(
-            "[Performance]   - CLAHE: {}ms",
-            start.elapsed().as_millis()
-        );
-    }
-
-    // Step 7: Morphological operations
-    match params.morphology.as_str() {
-        "erode" => {
-            let start = Instant::now();
-            processed = apply_erosion(&processed);
-            println!(
-                "[Performance]   - Erosion: {}ms",
-                start.elapsed().as_millis()
-            );
-        }
-        "dilate" => {
-            let start = Instant::now();
-            processed = apply_dilation(&processed);
-            println!(
-                "[Performance]   - Dilation: {}ms",
-                start.elapsed().as_millis()
-            );
-        }
-        "opening" => {
-            let start = Instant::now();
-            processed = apply_opening(&processed);
-            println!(
-                "[Performance]   - Opening: {}ms",
-                start.elapsed().as_millis()
-            );
-        }
-        "closing" => {
-            let start = Instant::now();
-            processed = apply_closing(&processed);
-            println!(
-                "[Performance]   - Closing: {}ms",
-                start.elapsed().as_millis()
-            );
-        }
-        _ => {}
-    }
+    tessdata: &TessdataLocator,
+    trace: &mut Trace,
+) -> Result<PreprocessedImage, String> {
+    let pipeline = match &params.pipeline {
+        Some(pipeline) => pipeline.clone(),
+        None => Pipeline::from_params(params),
+    };
 
-    // Step 8: Binarization (always last)
-    match params.binarization_method.as_str() {
-        "adaptive" => {
-            let start = Instant::now();
-            processed = apply_adaptive_threshold(&processed)?;
-            println!(
-                "[Performance]   - Adaptive threshold: {}ms",
-                start.elapsed().as_millis()
-            );
-        }
-        "otsu" => {
-            let start = Instant::now();
-            processed = apply_otsu_threshold(&processed)?;
-            println!(
-                "[Performance]   - Otsu threshold: {}ms",
-                start.elapsed().as_millis()
-            );
-        }
-        "mean" => {
-            let start = Instant::now();
-            processed = apply_mean_threshold(&processed)?;
-            println!(
-                "[Performance]   - Mean threshold: {}ms",
-                start.elapsed().as_millis()
-            );
-        }
-        "sauvola" => {
-            let start = Instant::now();
-            processed = apply_sauvola_threshold(&processed)?;
-            println!(
-                "[Performance]   - Sauvola threshold: {}ms",
-                start.elapsed().as_millis()
-            );
-        }
-        _ => {}
+    let mut ctx = StepContext::new(img, tessdata, trace);
+    if params.debug {
+        ctx.stages = Some(Vec::new());
     }
+    pipeline.run(&mut ctx)?;
 
-    Ok(processed)
+    Ok(PreprocessedImage {
+        image: ctx.image,
+        mapper: ctx.mapper,
+        orientation: ctx.orientation,
+        osd: ctx.osd,
+        scale_factor: ctx.scale_factor,
+        dewarp: ctx.dewarp,
+        table_grid: ctx.table_grid,
+        stages: ctx.stages.unwrap_or_default(),
+    })
 }
 
 /// Adaptive preprocessing based on image quality assessment
@@ -230,79 +288,341 @@ pub fn preprocess_image(
 pub fn adaptive_preprocess(
     img: DynamicImage,
     base_params: &ProcessingParams,
-) -> Result<DynamicImage, String> {
-    let metrics = assess_image_quality(&img);
+    tessdata: &TessdataLocator,
+    trace: &mut Trace,
+) -> Result<PreprocessedImage, String> {
+    let params = adaptive_params(&img, base_params, trace);
+    preprocess_image(img, &params, tessdata, trace)
+}
+
+/// Select processing parameters from the image's quality metrics
+fn adaptive_params(
+    img: &DynamicImage,
+    base_params: &ProcessingParams,
+    trace: &mut Trace,
+) -> ProcessingParams {
+    let metrics = assess_image_quality(img);
 
-    println!(
-        "[Quality] Blur: {:.1}, Contrast: {:.1}, Noise: {:.1}, Brightness: {:.1}",
-        metrics.blur_score, metrics.contrast_score, metrics.noise_level, metrics.brightness_level
+    log::info!(
+        "[Quality] Blur: {:.1}, Contrast: {:.1}, Noise: {:.1}, Brightness: {:.1}, Unevenness: {:.1}",
+        metrics.blur_score,
+        metrics.contrast_score,
+        metrics.noise_level,
+        metrics.brightness_level,
+        metrics.illumination_unevenness
     );
 
     // Create adaptive parameters based on quality metrics
     let mut params = ProcessingParams {
-        contrast: base_params.contrast,
-        brightness: base_params.brightness,
-        sharpness: base_params.sharpness,
-        binarization_method: base_params.binarization_method.clone(),
-        use_clahe: base_params.use_clahe,
-        gaussian_blur: base_params.gaussian_blur,
-        bilateral_filter: base_params.bilateral_filter,
-        morphology: base_params.morphology.clone(),
-        language: base_params.language.clone(),
-        correct_skew: base_params.correct_skew,
-        skew_method: base_params.skew_method.clone(),
-        remove_borders: base_params.remove_borders,
         adaptive_mode: false, // Prevent recursive adaptive processing
+        ..base_params.clone()
     };
 
+    // 0. Handle dark-mode and mixed-polarity images; once inverted, a dark
+    // theme no longer means a dark image
+    let mut brightness_level = metrics.brightness_level;
+    if metrics.polarity.polarity != Polarity::Normal {
+        params.normalize_polarity = true;
+        trace.decision(
+            "adaptive",
+            format!(
+                "polarity {:?} ({:.0}% of image light-on-dark) → invert dark regions",
+                metrics.polarity.polarity,
+                metrics.polarity.dark_fraction * 100.0
+            ),
+        );
+        if metrics.polarity.polarity == Polarity::Inverted {
+            brightness_level = 255.0 - brightness_level;
+        }
+    }
+
+    // 0b. Flatten shadows and lighting gradients, which otherwise black out
+    // part of the page under a global threshold and skew the brightness
+    // checks below. Dark panels read as uneven lighting, so inverted and
+    // mixed images are measured as the polarity step will leave them.
+    let illumination_unevenness = if metrics.polarity.polarity == Polarity::Normal {
+        metrics.illumination_unevenness
+    } else {
+        measure_illumination_unevenness(&normalize_polarity(img).0)
+    };
+    let flatten_background = illumination_unevenness > UNEVEN_ILLUMINATION_THRESHOLD;
+    if flatten_background {
+        params.normalize_background = true;
+        trace.decision(
+            "adaptive",
+            format!(
+                "illumination_unevenness {:.1} > {:.0} → flatten background",
+                illumination_unevenness, UNEVEN_ILLUMINATION_THRESHOLD
+            ),
+        );
+    }
+
     // 1. Handle blurry images
     if metrics.blur_score < 30.0 {
         params.sharpness = 2.0;
-        println!("[Adaptive] Detected blur -> Increasing sharpness to 2.0");
+        trace.decision(
+            "adaptive",
+            format!("blur_score {:.1} < 30 → sharpness 2.0", metrics.blur_score),
+        );
     } else if metrics.blur_score < 50.0 {
         params.sharpness = 1.5;
-        println!("[Adaptive] Moderate blur -> Setting sharpness to 1.5");
+        trace.decision(
+            "adaptive",
+            format!("blur_score {:.1} < 50 → sharpness 1.5", metrics.blur_score),
+        );
     }
 
     // 2. Handle low contrast
     if metrics.contrast_score < 40.0 {
         params.use_clahe = true;
         params.contrast = 1.5;
-        println!("[Adaptive] Low contrast -> Enabling CLAHE and increasing contrast to 1.5");
+        trace.decision(
+            "adaptive",
+            format!(
+                "contrast_score {:.1} < 40 → CLAHE, contrast 1.5",
+                metrics.contrast_score
+            ),
+        );
     } else if metrics.contrast_score < 60.0 {
         params.contrast = 1.3;
-        println!("[Adaptive] Moderate contrast -> Setting contrast to 1.3");
+        trace.decision(
+            "adaptive",
+            format!(
+                "contrast_score {:.1} < 60 → contrast 1.3",
+                metrics.contrast_score
+            ),
+        );
     }
 
     // 3. Handle noisy images
     if metrics.noise_level > 20.0 {
         params.bilateral_filter = true;
-        params.morphology = "opening".to_string();
-        println!("[Adaptive] High noise -> Enabling bilateral filter and opening");
+        params.morphology = MorphologyOperation::Opening;
+        trace.decision(
+            "adaptive",
+            format!(
+                "noise_level {:.1} > 20 → bilateral filter, opening",
+                metrics.noise_level
+            ),
+        );
     } else if metrics.noise_level > 12.0 {
         params.gaussian_blur = 1.0;
-        println!("[Adaptive] Moderate noise -> Applying Gaussian blur");
+        trace.decision(
+            "adaptive",
+            format!(
+                "noise_level {:.1} > 12 → Gaussian blur 1.0",
+                metrics.noise_level
+            ),
+        );
     }
 
-    // 4. Handle brightness issues
-    if metrics.brightness_level < 80.0 {
+    // 4. Handle brightness issues; a flattened page has a white background
+    // whatever its mean brightness was
+    if flatten_background {
+        trace.decision(
+            "adaptive",
+            "background flattened → brightness unchanged".to_string(),
+        );
+    } else if brightness_level < 80.0 {
         params.brightness = 0.2;
-        println!("[Adaptive] Dark image -> Increasing brightness");
-    } else if metrics.brightness_level > 200.0 {
+        trace.decision(
+            "adaptive",
+            format!(
+                "brightness_level {:.1} < 80 → brightness +0.2",
+                brightness_level
+            ),
+        );
+    } else if brightness_level > 200.0 {
         params.brightness = -0.1;
-        println!("[Adaptive] Bright image -> Decreasing brightness");
+        trace.decision(
+            "adaptive",
+            format!(
+                "brightness_level {:.1} > 200 → brightness -0.1",
+                brightness_level
+            ),
+        );
     }
 
     // 5. Choose optimal binarization method
-    if metrics.brightness_level < 100.0 || metrics.brightness_level > 180.0 {
-        if params.binarization_method != "none" {
-            params.binarization_method = "sauvola".to_string();
-            println!("[Adaptive] Uneven illumination -> Using Sauvola binarization");
+    if flatten_background {
+        if params.binarization_method == BinarizationMethod::None {
+            params.binarization_method = BinarizationMethod::Otsu;
+            trace.decision("adaptive", "background flattened → Otsu".to_string());
+        }
+    } else if !(100.0..=180.0).contains(&brightness_level) {
+        if params.binarization_method != BinarizationMethod::None {
+            params.binarization_method = BinarizationMethod::Sauvola;
+            trace.decision(
+                "adaptive",
+                format!(
+                    "brightness_level {:.1} outside 100-180 → Sauvola",
+                    brightness_level
+                ),
+            );
         }
-    } else if params.binarization_method == "none" {
-        params.binarization_method = "otsu".to_string();
-        println!("[Adaptive] Good conditions -> Using Otsu binarization");
+    } else if params.binarization_method == BinarizationMethod::None {
+        params.binarization_method = BinarizationMethod::Otsu;
+        trace.decision(
+            "adaptive",
+            format!(
+                "brightness_level {:.1} within 100-180 → Otsu",
+                brightness_level
+            ),
+        );
+    }
+
+    params
+}
+
+/// A preprocessed and recognized page
+pub struct RecognizedPage {
+    pub processed: PreprocessedImage,
+    /// Tesseract language string used for recognition
+    pub language: String,
+    /// Script detected in auto language mode
+    pub script: Option<ScriptDetection>,
+    pub text: String,
+    /// Layout tree with boxes in source image coordinates
+    pub blocks: Vec<OcrBlock>,
+}
+
+/// Preprocess and recognize one page image
+///
+/// # Arguments
+/// * `img` - The page image
+/// * `params` - Processing parameters
+/// * `tessdata` - Locator for installed models
+/// * `trace` - Receives step timings and decisions
+///
+/// # Returns
+/// The processed image, the language used and the recognized layout
+pub fn recognize_page(
+    img: DynamicImage,
+    params: &ProcessingParams,
+    tessdata: &TessdataLocator,
+    trace: &mut Trace,
+) -> Result<RecognizedPage, String> {
+    let preprocess_start = Instant::now();
+    let processed = if params.adaptive_mode {
+        adaptive_preprocess(img, params, tessdata, trace)?
+    } else {
+        preprocess_image(img, params, tessdata, trace)?
+    };
+    trace.timing("preprocess", preprocess_start.elapsed());
+
+    // Pick the language, detecting the script in auto mode
+    let (language, script) = select_language(
+        &processed.image,
+        &params.language,
+        tessdata,
+        processed.osd.as_ref(),
+    );
+    if let Some(script) = &script {
+        trace.decision(
+            "language",
+            format!(
+                "script {} ({:.2}) → {}",
+                script.script, script.confidence, language
+            ),
+        );
     }
+    let tessdata_path = tessdata.resolve(&language)?;
 
-    preprocess_image(img, &params)
+    let ocr_start = Instant::now();
+    let frame = processed.image.to_rgba8();
+    let (width, height) = frame.dimensions();
+    let mut tesseract = create_tesseract(tessdata_path.as_deref(), &language, &params.tesseract)?
+        .set_frame(
+            frame.as_raw(),
+            width as i32,
+            height as i32,
+            4,
+            4 * width as i32,
+        )
+        .map_err(|e| format!("Failed to set image: {}", e))?
+        .recognize()
+        .map_err(|e| format!("Failed to recognize text: {}", e))?;
+    let text = tesseract
+        .get_text()
+        .map_err(|e| format!("Failed to extract text: {}", e))?;
+    let tsv = tesseract
+        .get_tsv_text(0)
+        .map_err(|e| format!("Failed to extract layout: {}", e))?;
+    let blocks = parse_tsv(&tsv, &processed.mapper);
+    trace.timing("recognize", ocr_start.elapsed());
+
+    Ok(RecognizedPage {
+        processed,
+        language,
+        script,
+        text,
+        blocks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets::{built_in_presets, SCREENSHOT_PRESET};
+    use image::{GrayImage, Luma};
+
+    fn screenshot_params() -> ProcessingParams {
+        built_in_presets()
+            .into_iter()
+            .find(|preset| preset.name == SCREENSHOT_PRESET)
+            .map(|preset| preset.params)
+            .unwrap()
+    }
+
+    /// Lines of short strokes, like text
+    fn is_stroke(x: u32, y: u32) -> bool {
+        y % 16 < 6 && x % 8 < 3
+    }
+
+    /// Dark text on paper with a lamp shadow falling over the left side
+    fn shadowed_page(x: u32, y: u32) -> Luma<u8> {
+        let paper = if x < 200 { 100 } else { 235 };
+        Luma([if is_stroke(x, y) { paper / 10 } else { paper }])
+    }
+
+    #[test]
+    fn shadow_is_flattened_not_inverted() {
+        let img = DynamicImage::ImageLuma8(GrayImage::from_fn(400, 400, shadowed_page));
+        let params = adaptive_params(&img, &screenshot_params(), &mut Trace::new());
+        assert!(params.normalize_background);
+        assert!(!params.normalize_polarity);
+    }
+
+    #[test]
+    fn shadow_next_to_a_dark_panel_is_still_flattened() {
+        // A dark sidebar with light text beside the shadowed page
+        let img = GrayImage::from_fn(400, 400, |x, y| {
+            if x < 96 {
+                Luma([if is_stroke(x, y) { 220 } else { 30 }])
+            } else {
+                shadowed_page(x, y)
+            }
+        });
+        let params = adaptive_params(
+            &DynamicImage::ImageLuma8(img),
+            &screenshot_params(),
+            &mut Trace::new(),
+        );
+        assert!(params.normalize_polarity);
+        assert!(params.normalize_background);
+    }
+
+    #[test]
+    fn evenly_lit_page_is_left_alone() {
+        let img = GrayImage::from_fn(400, 400, |x, y| {
+            Luma([if is_stroke(x, y) { 20 } else { 235 }])
+        });
+        let params = adaptive_params(
+            &DynamicImage::ImageLuma8(img),
+            &screenshot_params(),
+            &mut Trace::new(),
+        );
+        assert!(!params.normalize_background);
+        assert!(!params.normalize_polarity);
+    }
 }
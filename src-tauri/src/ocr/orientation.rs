@@ -0,0 +1,84 @@
+//! Page orientation detection
+//!
+//! Prefers Tesseract OSD and falls back to the projection-profile
+//! heuristic when OSD is unavailable or finds too little text.
+
+use image::DynamicImage;
+use serde::Serialize;
+
+use super::language::TessdataLocator;
+use super::osd::{detect_orientation_script, OsdResult};
+use crate::preprocessing::detect_orientation_projection;
+
+/// Minimum OSD orientation confidence to rotate the page
+const MIN_OSD_CONFIDENCE: f32 = 5.0;
+
+/// Minimum projection heuristic confidence to rotate the page
+const MIN_PROJECTION_CONFIDENCE: f32 = 0.3;
+
+/// How the orientation was determined
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrientationMethod {
+    Osd,
+    Projection,
+}
+
+/// Detected page orientation
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrientationDetection {
+    /// Clockwise rotation applied to make text upright (0, 90, 180 or 270)
+    pub rotation_degrees: u32,
+    /// OSD confidence, or 0.0-1.0 agreement for the projection heuristic
+    pub confidence: f32,
+    pub method: OrientationMethod,
+}
+
+/// Detect the rotation needed to make a page upright
+///
+/// Low-confidence detections leave the page as is.
+///
+/// # Arguments
+/// * `img` - The page image
+/// * `tessdata` - Locator for `osd.traineddata`
+///
+/// # Returns
+/// The orientation decision, plus the OSD result when OSD succeeded so
+/// script detection can reuse it
+pub fn detect_page_orientation(
+    img: &DynamicImage,
+    tessdata: &TessdataLocator,
+) -> (OrientationDetection, Option<OsdResult>) {
+    let osd_path = tessdata.resolve("osd").ok().flatten();
+    match detect_orientation_script(&img.to_luma8(), osd_path.as_deref()) {
+        Ok(osd) => {
+            let rotation_degrees = if osd.orientation_confidence >= MIN_OSD_CONFIDENCE {
+                osd.rotation_degrees
+            } else {
+                0
+            };
+            let detection = OrientationDetection {
+                rotation_degrees,
+                confidence: osd.orientation_confidence,
+                method: OrientationMethod::Osd,
+            };
+            (detection, Some(osd))
+        }
+        Err(e) => {
+            log::warn!("[Orientation] {}; using projection profiles", e);
+            let estimate = detect_orientation_projection(img);
+            let rotation_degrees = if estimate.confidence >= MIN_PROJECTION_CONFIDENCE {
+                estimate.rotation_degrees
+            } else {
+                0
+            };
+            let detection = OrientationDetection {
+                rotation_degrees,
+                confidence: estimate.confidence,
+                method: OrientationMethod::Projection,
+            };
+            (detection, None)
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Tesseract orientation and script detection (OSD)
+//!
+//! The `tesseract` binding does not expose OSD, so this calls the C API
+//! directly with a short-lived engine initialized for `osd.traineddata`.
+
+use image::GrayImage;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use tesseract_sys::{
+    TessBaseAPICreate, TessBaseAPIDelete, TessBaseAPIDetectOrientationScript, TessBaseAPIInit3,
+    TessBaseAPISetImage, TessBaseAPISetSourceResolution,
+};
+
+/// Resolution reported to Tesseract for images without DPI metadata
+const OSD_SOURCE_DPI: i32 = 300;
+
+/// Result of orientation and script detection
+#[derive(Debug, Clone)]
+pub struct OsdResult {
+    /// Clockwise rotation (0, 90, 180 or 270) needed to make text upright
+    pub rotation_degrees: u32,
+    pub orientation_confidence: f32,
+    /// Tesseract script name, e.g. "Latin", "Han", "Cyrillic"
+    pub script: String,
+    pub script_confidence: f32,
+}
+
+/// Run orientation and script detection on an image
+///
+/// # Arguments
+/// * `img` - Grayscale image to analyze
+/// * `datapath` - tessdata directory containing `osd.traineddata`
+///
+/// # Returns
+/// Detected orientation and script, or an error if detection failed
+/// (typically because the image contains too little text)
+pub fn detect_orientation_script(
+    img: &GrayImage,
+    datapath: Option<&str>,
+) -> Result<OsdResult, String> {
+    let (width, height) = img.dimensions();
+    let datapath = datapath
+        .map(CString::new)
+        .transpose()
+        .map_err(|e| format!("Invalid tessdata path: {}", e))?;
+    let language = CString::new("osd").expect("static string");
+
+    // SAFETY: the handle is created and deleted here and never escapes;
+    // the image buffer outlives every call that reads it, and the script
+    // name is copied before the handle is deleted.
+    unsafe {
+        let handle = TessBaseAPICreate();
+        let result = (|| {
+            let datapath_ptr = datapath.as_ref().map_or(std::ptr::null(), |p| p.as_ptr());
+            if TessBaseAPIInit3(handle, datapath_ptr, language.as_ptr()) != 0 {
+                return Err(
+                    "Failed to initialize Tesseract OSD (is osd.traineddata installed?)"
+                        .to_string(),
+                );
+            }
+
+            TessBaseAPISetImage(
+                handle,
+                img.as_raw().as_ptr(),
+                width as i32,
+                height as i32,
+                1,
+                width as i32,
+            );
+            TessBaseAPISetSourceResolution(handle, OSD_SOURCE_DPI);
+
+            let mut orientation_degrees = 0;
+            let mut orientation_confidence = 0.0;
+            let mut script_name: *const c_char = std::ptr::null();
+            let mut script_confidence = 0.0;
+            let detected = TessBaseAPIDetectOrientationScript(
+                handle,
+                &mut orientation_degrees,
+                &mut orientation_confidence,
+                &mut script_name,
+                &mut script_confidence,
+            );
+            if detected == 0 {
+                return Err("Orientation and script detection found too little text".to_string());
+            }
+
+            let script = if script_name.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(script_name).to_string_lossy().into_owned()
+            };
+
+            Ok(OsdResult {
+                // Tesseract reports the page orientation; the correction is
+                // its complement (the "Rotate" value of `tesseract --psm 0`)
+                rotation_degrees: (360 - orientation_degrees.rem_euclid(360)) as u32 % 360,
+                orientation_confidence,
+                script,
+                script_confidence,
+            })
+        })();
+        TessBaseAPIDelete(handle);
+        result
+    }
+}
@@ -0,0 +1,79 @@
+//! Structured processing trace
+//!
+//! Records step timings and the reasons behind automatic decisions so
+//! they can be returned with the OCR result. Every event is also logged.
+
+use serde::Serialize;
+use std::time::Duration;
+
+/// A timing or decision recorded while processing
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceEvent {
+    /// Page the event belongs to; `None` for whole-document events
+    pub page: Option<u32>,
+    /// Step or phase, e.g. "deskew", "adaptive", "recognize"
+    pub step: String,
+    /// Time spent in the step
+    pub duration_ms: Option<u64>,
+    /// Why a decision was made, e.g. "blur_score 22.0 < 30 → sharpness 2.0"
+    pub reason: Option<String>,
+}
+
+/// Collects trace events in order
+#[derive(Debug, Default)]
+pub struct Trace {
+    page: Option<u32>,
+    events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attribute following events to `page` (or to the whole document)
+    pub fn set_page(&mut self, page: Option<u32>) {
+        self.page = page;
+    }
+
+    /// Record how long a step took
+    pub fn timing(&mut self, step: &str, duration: Duration) {
+        let duration_ms = duration.as_millis() as u64;
+        log::info!("[Performance] {}: {}ms", step, duration_ms);
+        self.events.push(TraceEvent {
+            page: self.page,
+            step: step.to_string(),
+            duration_ms: Some(duration_ms),
+            reason: None,
+        });
+    }
+
+    /// Record a decision and the reason for it
+    pub fn decision(&mut self, step: &str, reason: String) {
+        log::info!("[{}] {}", step, reason);
+        self.events.push(TraceEvent {
+            page: self.page,
+            step: step.to_string(),
+            duration_ms: None,
+            reason: Some(reason),
+        });
+    }
+
+    /// Start a separate trace for the same page, e.g. for a parallel task
+    pub fn fork(&self) -> Self {
+        Self {
+            page: self.page,
+            events: Vec::new(),
+        }
+    }
+
+    /// Append the events of a forked trace
+    pub fn merge(&mut self, other: Trace) {
+        self.events.extend(other.events);
+    }
+
+    pub fn into_events(self) -> Vec<TraceEvent> {
+        self.events
+    }
+}
@@ -0,0 +1,167 @@
+//! Glyphless TrueType font for the invisible text layer
+//!
+//! Viewers and PDF/A validators expect a CIDFontType2 to embed its font
+//! program. Like Tesseract's `pdf.ttf`, this font has only `.notdef` and
+//! a single empty glyph with a fixed advance; every CID maps to that glyph.
+
+use super::GLYPH_WIDTH;
+
+/// Font units per em; 1000 so advances match PDF text space units
+const UNITS_PER_EM: u16 = 1000;
+
+/// `.notdef` and the empty glyph every CID maps to
+const NUM_GLYPHS: u16 = 2;
+
+/// Glyph id of the empty glyph
+pub const EMPTY_GLYPH: u16 = 1;
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_i16(out: &mut Vec<u8>, value: i16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Sum of the data as big-endian 32-bit words, zero-padded
+fn table_checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Font header; the checksum adjustment is filled in once the file is built
+fn head_table() -> Vec<u8> {
+    let mut t = Vec::new();
+    put_u32(&mut t, 0x0001_0000); // version
+    put_u32(&mut t, 0x0001_0000); // font revision
+    put_u32(&mut t, 0); // checksum adjustment
+    put_u32(&mut t, 0x5F0F_3CF5); // magic number
+    put_u16(&mut t, 0x000B); // flags: baseline and lsb at 0, integer scaling
+    put_u16(&mut t, UNITS_PER_EM);
+    t.extend_from_slice(&[0; 16]); // created, modified
+    put_i16(&mut t, 0); // x min
+    put_i16(&mut t, 0); // y min
+    put_i16(&mut t, GLYPH_WIDTH as i16); // x max
+    put_i16(&mut t, UNITS_PER_EM as i16); // y max
+    put_u16(&mut t, 0); // mac style
+    put_u16(&mut t, 3); // lowest recommended ppem
+    put_i16(&mut t, 2); // font direction hint
+    put_i16(&mut t, 0); // short loca offsets
+    put_i16(&mut t, 0); // glyph data format
+    t
+}
+
+fn hhea_table() -> Vec<u8> {
+    let mut t = Vec::new();
+    put_u32(&mut t, 0x0001_0000); // version
+    put_i16(&mut t, UNITS_PER_EM as i16); // ascender
+    put_i16(&mut t, 0); // descender
+    put_i16(&mut t, 0); // line gap
+    put_u16(&mut t, GLYPH_WIDTH as u16); // advance width max
+    put_i16(&mut t, 0); // min left side bearing
+    put_i16(&mut t, 0); // min right side bearing
+    put_i16(&mut t, GLYPH_WIDTH as i16); // x max extent
+    put_i16(&mut t, 1); // caret slope rise
+    put_i16(&mut t, 0); // caret slope run
+    put_i16(&mut t, 0); // caret offset
+    t.extend_from_slice(&[0; 8]); // reserved
+    put_i16(&mut t, 0); // metric data format
+    put_u16(&mut t, NUM_GLYPHS); // number of horizontal metrics
+    t
+}
+
+fn maxp_table() -> Vec<u8> {
+    let mut t = Vec::new();
+    put_u32(&mut t, 0x0001_0000); // version 1.0
+    put_u16(&mut t, NUM_GLYPHS);
+    put_u16(&mut t, 0); // max points
+    put_u16(&mut t, 0); // max contours
+    put_u16(&mut t, 0); // max composite points
+    put_u16(&mut t, 0); // max composite contours
+    put_u16(&mut t, 2); // max zones
+    t.extend_from_slice(&[0; 16]); // remaining limits, all unused
+    t
+}
+
+fn hmtx_table() -> Vec<u8> {
+    let mut t = Vec::new();
+    for _ in 0..NUM_GLYPHS {
+        put_u16(&mut t, GLYPH_WIDTH as u16); // advance width
+        put_i16(&mut t, 0); // left side bearing
+    }
+    t
+}
+
+/// Short offsets into an empty `glyf` table: every glyph has no outline
+fn loca_table() -> Vec<u8> {
+    vec![0; 2 * (NUM_GLYPHS as usize + 1)]
+}
+
+fn post_table() -> Vec<u8> {
+    let mut t = Vec::new();
+    put_u32(&mut t, 0x0003_0000); // version 3.0: no glyph names
+    put_u32(&mut t, 0); // italic angle
+    put_i16(&mut t, -100); // underline position
+    put_i16(&mut t, 50); // underline thickness
+    put_u32(&mut t, 1); // fixed pitch
+    t.extend_from_slice(&[0; 16]); // memory usage hints
+    t
+}
+
+/// Build the glyphless font program
+///
+/// # Returns
+/// A TrueType font file for embedding as `FontFile2`
+pub fn glyphless_font() -> Vec<u8> {
+    // Table directory entries must be sorted by tag
+    let tables: [(&[u8; 4], Vec<u8>); 7] = [
+        (b"glyf", Vec::new()),
+        (b"head", head_table()),
+        (b"hhea", hhea_table()),
+        (b"hmtx", hmtx_table()),
+        (b"loca", loca_table()),
+        (b"maxp", maxp_table()),
+        (b"post", post_table()),
+    ];
+
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.leading_zeros() as u16;
+    let search_range = 16 << entry_selector;
+
+    let mut font = Vec::new();
+    put_u32(&mut font, 0x0001_0000); // TrueType outlines
+    put_u16(&mut font, num_tables);
+    put_u16(&mut font, search_range);
+    put_u16(&mut font, entry_selector);
+    put_u16(&mut font, num_tables * 16 - search_range);
+
+    let mut offset = 12 + 16 * tables.len();
+    let mut data = Vec::new();
+    let mut head_offset = 0;
+    for (tag, table) in &tables {
+        if *tag == b"head" {
+            head_offset = offset;
+        }
+        font.extend_from_slice(*tag);
+        put_u32(&mut font, table_checksum(table));
+        put_u32(&mut font, offset as u32);
+        put_u32(&mut font, table.len() as u32);
+
+        data.extend_from_slice(table);
+        let padded = table.len().div_ceil(4) * 4;
+        data.resize(data.len() + padded - table.len(), 0);
+        offset += padded;
+    }
+    font.extend_from_slice(&data);
+
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(table_checksum(&font));
+    font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    font
+}
@@ -0,0 +1,239 @@
+//! Searchable PDF generation
+//!
+//! Writes each page as the original image with an invisible text layer
+//! positioned over the recognized words, so the PDF can be searched and
+//! its text selected and copied.
+//!
+//! The text layer uses a glyphless Type0 font: every distinct character is
+//! assigned its own CID, and a ToUnicode map translates CIDs back to text.
+//! The embedded font program has a single empty glyph, since the text is
+//! never painted.
+
+mod font;
+
+use image::DynamicImage;
+use miniz_oxide::deflate::{compress_to_vec_zlib, CompressionLevel};
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, TextRenderingMode, UnicodeCmap};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str};
+use std::collections::HashMap;
+
+use crate::ocr::OcrBlock;
+
+const IMAGE_NAME: Name = Name(b"Im1");
+const FONT_NAME: Name = Name(b"F1");
+
+/// Resolution assumed for images that do not record one
+pub const DEFAULT_DPI: f32 = 300.0;
+
+/// Glyph advance of the glyphless font in text space units (1/1000 em)
+const GLYPH_WIDTH: f32 = 500.0;
+
+/// A page to write: the visible image and the OCR layout over it
+pub struct PdfPage<'a> {
+    pub image: &'a DynamicImage,
+    /// Layout with boxes in `image` pixel coordinates
+    pub blocks: &'a [OcrBlock],
+    /// Resolution of `image`, used to convert pixels to PDF points
+    pub dpi: f32,
+}
+
+/// Assigns a CID to every distinct character in the text layer
+#[derive(Default)]
+struct CidMap {
+    cids: HashMap<char, u16>,
+    chars: Vec<char>,
+}
+
+impl CidMap {
+    /// Encode text as big-endian two-byte CIDs
+    fn encode(&mut self, text: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(text.len() * 2);
+        for c in text.chars() {
+            let cid = match self.cids.get(&c) {
+                Some(&cid) => cid,
+                // CID 0 is reserved for .notdef; stop assigning when full
+                None if self.chars.len() < u16::MAX as usize - 1 => {
+                    self.chars.push(c);
+                    let cid = self.chars.len() as u16;
+                    self.cids.insert(c, cid);
+                    cid
+                }
+                None => 0,
+            };
+            bytes.extend_from_slice(&cid.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+/// Write a searchable PDF
+///
+/// # Arguments
+/// * `pages` - Pages in output order
+///
+/// # Returns
+/// The encoded PDF file
+pub fn write_searchable_pdf(pages: &[PdfPage]) -> Result<Vec<u8>, String> {
+    if pages.is_empty() {
+        return Err("No pages to write".to_string());
+    }
+    if let Some(page) = pages
+        .iter()
+        .find(|page| !page.dpi.is_finite() || page.dpi <= 0.0)
+    {
+        return Err(format!("Invalid DPI: {}", page.dpi));
+    }
+
+    let mut pdf = Pdf::new();
+    let mut next_ref = Ref::new(1);
+
+    let catalog_id = next_ref.bump();
+    let page_tree_id = next_ref.bump();
+    let font_id = next_ref.bump();
+    let cid_font_id = next_ref.bump();
+    let descriptor_id = next_ref.bump();
+    let to_unicode_id = next_ref.bump();
+    let font_file_id = next_ref.bump();
+    let cid_to_gid_id = next_ref.bump();
+
+    let mut cid_map = CidMap::default();
+    let mut page_ids = Vec::with_capacity(pages.len());
+
+    for page in pages {
+        let page_id = next_ref.bump();
+        let image_id = next_ref.bump();
+        let content_id = next_ref.bump();
+        page_ids.push(page_id);
+
+        let scale = 72.0 / page.dpi;
+        let rgb = page.image.to_rgb8();
+        let (width, height) = rgb.dimensions();
+        let page_width = width as f32 * scale;
+        let page_height = height as f32 * scale;
+
+        let encoded = compress_to_vec_zlib(rgb.as_raw(), CompressionLevel::DefaultLevel as u8);
+        let mut image = pdf.image_xobject(image_id, &encoded);
+        image.filter(Filter::FlateDecode);
+        image.width(width as i32);
+        image.height(height as i32);
+        image.color_space().device_rgb();
+        image.bits_per_component(8);
+        image.finish();
+
+        let mut content = Content::new();
+        content.save_state();
+        content.transform([page_width, 0.0, 0.0, page_height, 0.0, 0.0]);
+        content.x_object(IMAGE_NAME);
+        content.restore_state();
+
+        content.begin_text();
+        content.set_text_rendering_mode(TextRenderingMode::Invisible);
+        for line in page
+            .blocks
+            .iter()
+            .flat_map(|block| &block.paragraphs)
+            .flat_map(|paragraph| &paragraph.lines)
+        {
+            for (i, word) in line.words.iter().enumerate() {
+                // Trailing space lets viewers recover word breaks; it spans
+                // the gap up to the next word
+                let (text, box_width) = match line.words.get(i + 1) {
+                    Some(next) => (
+                        format!("{} ", word.text),
+                        next.bbox.left.saturating_sub(word.bbox.left),
+                    ),
+                    None => (word.text.clone(), word.bbox.width),
+                };
+                let glyphs = text.chars().count();
+                if glyphs == 0 || word.bbox.height == 0 || box_width == 0 {
+                    continue;
+                }
+
+                let font_size = word.bbox.height as f32 * scale;
+                let natural_width = glyphs as f32 * GLYPH_WIDTH / 1000.0 * font_size;
+                let target_width = box_width as f32 * scale;
+
+                content.set_font(FONT_NAME, font_size);
+                content.set_horizontal_scaling(100.0 * target_width / natural_width);
+                content.set_text_matrix([
+                    1.0,
+                    0.0,
+                    0.0,
+                    1.0,
+                    word.bbox.left as f32 * scale,
+                    page_height - word.bbox.bottom() as f32 * scale,
+                ]);
+                content.show(Str(&cid_map.encode(&text)));
+            }
+        }
+        content.end_text();
+        pdf.stream(content_id, &content.finish());
+
+        let mut pdf_page = pdf.page(page_id);
+        pdf_page.media_box(Rect::new(0.0, 0.0, page_width, page_height));
+        pdf_page.parent(page_tree_id);
+        pdf_page.contents(content_id);
+        let mut resources = pdf_page.resources();
+        resources.x_objects().pair(IMAGE_NAME, image_id);
+        resources.fonts().pair(FONT_NAME, font_id);
+        resources.finish();
+        pdf_page.finish();
+    }
+
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(page_ids.len() as i32);
+
+    let system_info = SystemInfo {
+        registry: Str(b"Adobe"),
+        ordering: Str(b"Identity"),
+        supplement: 0,
+    };
+
+    pdf.type0_font(font_id)
+        .base_font(Name(b"GlyphLessFont"))
+        .encoding_predefined(Name(b"Identity-H"))
+        .descendant_font(cid_font_id)
+        .to_unicode(to_unicode_id);
+
+    pdf.cid_font(cid_font_id)
+        .subtype(CidFontType::Type2)
+        .base_font(Name(b"GlyphLessFont"))
+        .system_info(system_info)
+        .font_descriptor(descriptor_id)
+        .default_width(GLYPH_WIDTH)
+        .cid_to_gid_map_stream(cid_to_gid_id);
+
+    // Every CID shows the same empty glyph
+    let cid_to_gid: Vec<u8> = font::EMPTY_GLYPH.to_be_bytes().repeat(1 << 16);
+    let cid_to_gid = compress_to_vec_zlib(&cid_to_gid, CompressionLevel::DefaultLevel as u8);
+    pdf.stream(cid_to_gid_id, &cid_to_gid)
+        .filter(Filter::FlateDecode);
+
+    let font_program = font::glyphless_font();
+    let compressed_font = compress_to_vec_zlib(&font_program, CompressionLevel::DefaultLevel as u8);
+    pdf.stream(font_file_id, &compressed_font)
+        .filter(Filter::FlateDecode)
+        .pair(Name(b"Length1"), font_program.len() as i32);
+
+    // Ascent 1000 / descent 0 makes selection highlights match word boxes
+    pdf.font_descriptor(descriptor_id)
+        .name(Name(b"GlyphLessFont"))
+        .flags(FontFlags::SYMBOLIC)
+        .bbox(Rect::new(0.0, 0.0, GLYPH_WIDTH, 1000.0))
+        .italic_angle(0.0)
+        .ascent(1000.0)
+        .descent(0.0)
+        .cap_height(1000.0)
+        .stem_v(80.0)
+        .font_file2(font_file_id);
+
+    let mut cmap = UnicodeCmap::<u16>::new(Name(b"Custom"), system_info);
+    for (index, c) in cid_map.chars.iter().enumerate() {
+        cmap.pair(index as u16 + 1, *c);
+    }
+    pdf.cmap(to_unicode_id, &cmap.finish());
+
+    Ok(pdf.finish())
+}
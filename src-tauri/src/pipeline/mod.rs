@@ -0,0 +1,362 @@
+//! Composable preprocessing pipeline
+//!
+//! Preprocessing is a list of steps that each implement [`PreprocessStep`].
+//! The list is serializable, so the frontend can reorder, repeat or disable
+//! steps (e.g. run morphology after binarization). When no pipeline is
+//! given, [`Pipeline::from_params`] builds the classic fixed order from the
+//! individual processing parameters.
+
+mod steps;
+
+pub use steps::{
+    BilateralFilter, Binarize, Brightness, Clahe, ColorDropout, Contrast, Deskew, Despeckle,
+    Dewarp, GaussianBlur, Morphology, NormalizeBackground, NormalizePolarity, NormalizeScale,
+    Orientation, Perspective, RemoveBorders, RemoveLines, Sharpen,
+};
+
+use image::DynamicImage;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::binarization::BinarizationMethod;
+use crate::morphology::MorphologyOperation;
+use crate::ocr::{OrientationDetection, OsdResult, ProcessingParams, TessdataLocator, Trace};
+use crate::preprocessing::{
+    BackgroundMethod, CoordinateMapper, DewarpReport, DropoutMode, FlattenMode, ResampleFilter,
+    TableGrid, DEFAULT_SPECK_SIZE, DEFAULT_TARGET_TEXT_HEIGHT,
+};
+
+/// Longest side of stage thumbnails recorded in debug mode
+const THUMBNAIL_SIZE: u32 = 512;
+
+/// Output of one step, recorded in debug mode
+pub struct StageOutput {
+    pub name: &'static str,
+    /// Parameters the step ran with
+    pub params: serde_json::Value,
+    pub duration_ms: u64,
+    /// Downscaled copy of the step's output image
+    pub thumbnail: DynamicImage,
+}
+
+/// State threaded through the pipeline steps
+pub struct StepContext<'a> {
+    pub image: DynamicImage,
+    /// Maps coordinates on `image` back to the input image
+    pub mapper: CoordinateMapper,
+    /// Orientation detected by an orientation step
+    pub orientation: Option<OrientationDetection>,
+    /// OSD result from orientation detection, reused for script detection
+    pub osd: Option<OsdResult>,
+    /// Combined resampling factor applied by scale normalization
+    pub scale_factor: f32,
+    /// Curvature removed by a dewarp step
+    pub dewarp: Option<DewarpReport>,
+    /// Ruled lines erased by a line removal step, in source coordinates
+    pub table_grid: Option<TableGrid>,
+    /// Locator for the OSD model
+    pub tessdata: &'a TessdataLocator,
+    /// Step timings and decisions
+    pub trace: &'a mut Trace,
+    /// Per-step outputs, recorded only when set (debug mode)
+    pub stages: Option<Vec<StageOutput>>,
+}
+
+impl<'a> StepContext<'a> {
+    pub fn new(image: DynamicImage, tessdata: &'a TessdataLocator, trace: &'a mut Trace) -> Self {
+        let mapper = CoordinateMapper::new(image.width(), image.height());
+        Self {
+            image,
+            mapper,
+            orientation: None,
+            osd: None,
+            scale_factor: 1.0,
+            dewarp: None,
+            table_grid: None,
+            tessdata,
+            trace,
+            stages: None,
+        }
+    }
+}
+
+/// A single preprocessing operation
+pub trait PreprocessStep {
+    /// Stable step identifier, matching the serialized `type`
+    fn name(&self) -> &'static str;
+
+    /// Step parameters as JSON, for logging and diagnostics
+    fn params(&self) -> serde_json::Value;
+
+    /// Check parameter ranges before any work is done
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Apply the step to the current image
+    ///
+    /// Steps that change the geometry must extend `ctx.mapper` so word
+    /// boxes can be mapped back to the input image.
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String>;
+}
+
+/// Serializable step configuration
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StepConfig {
+    Perspective(Perspective),
+    RemoveBorders(RemoveBorders),
+    Orientation(Orientation),
+    Deskew(Deskew),
+    Dewarp(Dewarp),
+    NormalizeScale(NormalizeScale),
+    NormalizePolarity(NormalizePolarity),
+    ColorDropout(ColorDropout),
+    NormalizeBackground(NormalizeBackground),
+    RemoveLines(RemoveLines),
+    BilateralFilter(BilateralFilter),
+    GaussianBlur(GaussianBlur),
+    Brightness(Brightness),
+    Contrast(Contrast),
+    Sharpen(Sharpen),
+    Clahe(Clahe),
+    Morphology(Morphology),
+    Binarize(Binarize),
+    Despeckle(Despeckle),
+}
+
+impl StepConfig {
+    /// The step implementation for this configuration
+    pub fn step(&self) -> &dyn PreprocessStep {
+        match self {
+            StepConfig::Perspective(step) => step,
+            StepConfig::RemoveBorders(step) => step,
+            StepConfig::Orientation(step) => step,
+            StepConfig::Deskew(step) => step,
+            StepConfig::Dewarp(step) => step,
+            StepConfig::NormalizeScale(step) => step,
+            StepConfig::NormalizePolarity(step) => step,
+            StepConfig::ColorDropout(step) => step,
+            StepConfig::NormalizeBackground(step) => step,
+            StepConfig::RemoveLines(step) => step,
+            StepConfig::BilateralFilter(step) => step,
+            StepConfig::GaussianBlur(step) => step,
+            StepConfig::Brightness(step) => step,
+            StepConfig::Contrast(step) => step,
+            StepConfig::Sharpen(step) => step,
+            StepConfig::Clahe(step) => step,
+            StepConfig::Morphology(step) => step,
+            StepConfig::Binarize(step) => step,
+            StepConfig::Despeckle(step) => step,
+        }
+    }
+}
+
+/// A pipeline entry: a step and whether it runs
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStep {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub config: StepConfig,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Ordered list of preprocessing steps
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Pipeline {
+    pub steps: Vec<PipelineStep>,
+}
+
+impl Pipeline {
+    fn push(&mut self, enabled: bool, config: StepConfig) {
+        self.steps.push(PipelineStep { enabled, config });
+    }
+
+    /// Build the default pipeline from individual processing parameters
+    ///
+    /// Every step appears in the classic order, disabled when its
+    /// parameter turns it off:
+    /// 1. Perspective correction
+    /// 2. Border removal
+    /// 3. Orientation correction (90°/180°/270°)
+    /// 4. Geometric correction (deskewing)
+    /// 5. Page curl dewarping
+    /// 6. Scale normalization to the target text height
+    /// 7. Polarity normalization (dark-mode inversion)
+    /// 8. Color dropout, keeping the dominant ink color
+    /// 9. Background normalization (shadow removal)
+    /// 10. Table and form line removal
+    /// 11. Noise reduction (bilateral filter, or else Gaussian blur)
+    /// 12. Brightness/Contrast adjustment
+    /// 13. Sharpening
+    /// 14. Contrast enhancement (CLAHE)
+    /// 15. Morphological operations
+    /// 16. Binarization
+    /// 17. Despeckling of the binarized page
+    pub fn from_params(params: &ProcessingParams) -> Self {
+        let mut pipeline = Pipeline::default();
+        pipeline.push(
+            params.correct_perspective || params.document_corners.is_some(),
+            StepConfig::Perspective(Perspective {
+                corners: params.document_corners,
+            }),
+        );
+        pipeline.push(
+            params.remove_borders,
+            StepConfig::RemoveBorders(RemoveBorders {}),
+        );
+        pipeline.push(
+            params.detect_orientation,
+            StepConfig::Orientation(Orientation {}),
+        );
+        pipeline.push(
+            params.correct_skew,
+            StepConfig::Deskew(Deskew {
+                method: params.skew_method,
+            }),
+        );
+        pipeline.push(params.dewarp, StepConfig::Dewarp(Dewarp {}));
+        pipeline.push(
+            params.normalize_scale,
+            StepConfig::NormalizeScale(NormalizeScale {
+                target_height: params
+                    .target_text_height
+                    .unwrap_or(DEFAULT_TARGET_TEXT_HEIGHT),
+                filter: ResampleFilter::default(),
+            }),
+        );
+        pipeline.push(
+            params.normalize_polarity,
+            StepConfig::NormalizePolarity(NormalizePolarity {}),
+        );
+        pipeline.push(
+            params.color_dropout,
+            StepConfig::ColorDropout(ColorDropout {
+                mode: DropoutMode::Auto,
+                hues: Vec::new(),
+            }),
+        );
+        pipeline.push(
+            params.normalize_background,
+            StepConfig::NormalizeBackground(NormalizeBackground {
+                method: BackgroundMethod::default(),
+                mode: FlattenMode::default(),
+                kernel_size: None,
+            }),
+        );
+        pipeline.push(
+            params.remove_lines || params.detect_tables,
+            StepConfig::RemoveLines(RemoveLines {
+                min_length: None,
+                return_grid: params.return_table_grid || params.detect_tables,
+            }),
+        );
+        pipeline.push(
+            params.bilateral_filter,
+            StepConfig::BilateralFilter(BilateralFilter {}),
+        );
+        pipeline.push(
+            !params.bilateral_filter && params.gaussian_blur > 0.0,
+            StepConfig::GaussianBlur(GaussianBlur {
+                sigma: params.gaussian_blur,
+            }),
+        );
+        pipeline.push(
+            params.brightness != 0.0,
+            StepConfig::Brightness(Brightness {
+                amount: params.brightness,
+            }),
+        );
+        pipeline.push(
+            params.contrast != 1.0,
+            StepConfig::Contrast(Contrast {
+                factor: params.contrast,
+            }),
+        );
+        pipeline.push(
+            params.sharpness > 1.0,
+            StepConfig::Sharpen(Sharpen {
+                amount: params.sharpness,
+            }),
+        );
+        pipeline.push(
+            params.use_clahe,
+            StepConfig::Clahe(Clahe {
+                tile_grid: params.clahe_tile_grid,
+                clip_limit: params.clahe_clip_limit,
+            }),
+        );
+        pipeline.push(
+            params.morphology != MorphologyOperation::None,
+            StepConfig::Morphology(Morphology {
+                operation: params.morphology,
+            }),
+        );
+        pipeline.push(
+            params.binarization_method != BinarizationMethod::None,
+            StepConfig::Binarize(Binarize {
+                method: params.binarization_method,
+                window: params.threshold_window,
+                k: params.threshold_k,
+                r: params.threshold_r,
+            }),
+        );
+        pipeline.push(
+            params.despeckle,
+            StepConfig::Despeckle(Despeckle {
+                speck_size: DEFAULT_SPECK_SIZE,
+                fill_holes: true,
+                remove_border: params.despeckle_border,
+            }),
+        );
+        pipeline
+    }
+
+    /// Check the parameters of every step, enabled or not
+    pub fn validate(&self) -> Result<(), String> {
+        for entry in &self.steps {
+            let step = entry.config.step();
+            step.validate()
+                .map_err(|e| format!("Preprocessing step '{}': {}", step.name(), e))?;
+        }
+        Ok(())
+    }
+
+    /// Run the enabled steps in order
+    ///
+    /// # Arguments
+    /// * `ctx` - Image and state to process in place
+    pub fn run(&self, ctx: &mut StepContext) -> Result<(), String> {
+        for entry in self.steps.iter().filter(|entry| entry.enabled) {
+            let step = entry.config.step();
+            let start = Instant::now();
+            step.apply(ctx)
+                .map_err(|e| format!("Preprocessing step '{}' failed: {}", step.name(), e))?;
+            let duration = start.elapsed();
+            log::debug!("[Pipeline] {} {}", step.name(), step.params());
+            ctx.trace.timing(step.name(), duration);
+
+            if let Some(stages) = ctx.stages.as_mut() {
+                let thumbnail = if ctx.image.width().max(ctx.image.height()) > THUMBNAIL_SIZE {
+                    ctx.image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+                } else {
+                    ctx.image.clone()
+                };
+                stages.push(StageOutput {
+                    name: step.name(),
+                    params: step.params(),
+                    duration_ms: duration.as_millis() as u64,
+                    thumbnail,
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,788 @@
+//! Preprocessing step implementations
+//!
+//! Each step wraps one operation from the preprocessing, binarization or
+//! morphology modules.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{PreprocessStep, StepContext};
+use crate::binarization::{
+    apply_adaptive_threshold, apply_clahe, apply_local_threshold, apply_mean_threshold,
+    apply_otsu_threshold, validate_clahe_params, BinarizationMethod, LocalThresholdParams,
+    DEFAULT_CLAHE_CLIP_LIMIT, DEFAULT_CLAHE_TILE_GRID, DEFAULT_SAUVOLA_R,
+};
+use crate::morphology::{apply_morphology, MorphologyOperation};
+use crate::ocr::detect_page_orientation;
+use crate::preprocessing::{
+    adjust_brightness, adjust_contrast, adjust_sharpness, apply_bilateral_filter,
+    apply_gaussian_blur, correct_perspective, correct_skew, correct_skew_projection, despeckle,
+    detect_document_corners, dewarp_page, drop_colors, estimate_text_height, normalization_factor,
+    normalize_background, normalize_polarity, remove_borders, remove_lines, rescale,
+    rotate_orthogonal, BackgroundMethod, DespeckleOptions, DropoutMode, FlattenMode, HueSpec,
+    Polarity, ResampleFilter, SkewMethod, DEFAULT_SPECK_SIZE, DEFAULT_TARGET_TEXT_HEIGHT,
+};
+
+/// Serialize a step's fields as its parameters
+fn to_params<T: Serialize>(step: &T) -> serde_json::Value {
+    serde_json::to_value(step).unwrap_or(serde_json::Value::Null)
+}
+
+/// Reject values outside `[min, max]`, including NaN
+fn check_range(name: &str, value: f32, min: f32, max: f32) -> Result<(), String> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid {}: {} (expected {} to {})",
+            name, value, min, max
+        ))
+    }
+}
+
+/// Rectify a photographed page to an upright rectangle
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Perspective {
+    /// Page corners as `[x, y]` in the order top-left, top-right,
+    /// bottom-right, bottom-left; detected automatically when omitted
+    #[serde(default)]
+    pub corners: Option<[[f32; 2]; 4]>,
+}
+
+impl PreprocessStep for Perspective {
+    fn name(&self) -> &'static str {
+        "perspective"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(corners) = &self.corners {
+            if corners.iter().flatten().any(|value| !value.is_finite()) {
+                return Err("Document corners must be finite numbers".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let corners = match self.corners {
+            Some(corners) => corners.map(|[x, y]| (x, y)),
+            None => match detect_document_corners(&ctx.image) {
+                Some(corners) => corners,
+                None => {
+                    ctx.trace.decision(
+                        "perspective",
+                        "no page outline found → unchanged".to_string(),
+                    );
+                    return Ok(());
+                }
+            },
+        };
+        let (warped, transform) = correct_perspective(&ctx.image, corners)?;
+        ctx.trace.decision(
+            "perspective",
+            format!(
+                "{} corners {:?} → {}x{}",
+                if self.corners.is_some() {
+                    "manual"
+                } else {
+                    "detected"
+                },
+                corners.map(|(x, y)| (x.round() as i32, y.round() as i32)),
+                warped.width(),
+                warped.height()
+            ),
+        );
+        ctx.image = warped;
+        ctx.mapper.extend(transform);
+        Ok(())
+    }
+}
+
+/// Crop uniform borders around the content
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RemoveBorders {}
+
+impl PreprocessStep for RemoveBorders {
+    fn name(&self) -> &'static str {
+        "removeBorders"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let (cropped, transform) = remove_borders(&ctx.image);
+        ctx.image = cropped;
+        ctx.mapper.extend(transform);
+        Ok(())
+    }
+}
+
+/// Detect and fix pages rotated by 90°, 180° or 270°
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Orientation {}
+
+impl PreprocessStep for Orientation {
+    fn name(&self) -> &'static str {
+        "orientation"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let (detection, osd) = detect_page_orientation(&ctx.image, ctx.tessdata);
+        let (rotated, transform) = rotate_orthogonal(&ctx.image, detection.rotation_degrees)?;
+        ctx.trace.decision(
+            "orientation",
+            format!(
+                "{:?} → {}° (confidence {:.2})",
+                detection.method, detection.rotation_degrees, detection.confidence
+            ),
+        );
+        ctx.image = rotated;
+        ctx.mapper.extend(transform);
+        ctx.orientation = Some(detection);
+        if osd.is_some() {
+            ctx.osd = osd;
+        }
+        Ok(())
+    }
+}
+
+/// Correct small skew angles
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Deskew {
+    #[serde(default = "default_skew_method")]
+    pub method: SkewMethod,
+}
+
+fn default_skew_method() -> SkewMethod {
+    SkewMethod::Projection
+}
+
+impl PreprocessStep for Deskew {
+    fn name(&self) -> &'static str {
+        "deskew"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let (deskewed, transform) = match self.method {
+            SkewMethod::Hough => correct_skew(&ctx.image)?,
+            SkewMethod::Projection => correct_skew_projection(&ctx.image)?,
+        };
+        ctx.image = deskewed;
+        ctx.mapper.extend(transform);
+        Ok(())
+    }
+}
+
+/// Straighten text lines curved by page curl
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Dewarp {}
+
+impl PreprocessStep for Dewarp {
+    fn name(&self) -> &'static str {
+        "dewarp"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let (dewarped, transform, report) = dewarp_page(&ctx.image);
+        let reason = match &report {
+            Some(report) => format!(
+                "{} lines, curvature {:.1}px → {:.1}px ({:.0}% removed)",
+                report.lines,
+                report.curvature_before,
+                report.curvature_after,
+                report.curvature_removed * 100.0
+            ),
+            None => "no significant curvature → unchanged".to_string(),
+        };
+        ctx.trace.decision("dewarp", reason);
+        ctx.image = dewarped;
+        ctx.mapper.extend(transform);
+        if report.is_some() {
+            ctx.dewarp = report;
+        }
+        Ok(())
+    }
+}
+
+/// Resample so the dominant text height matches a target
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizeScale {
+    /// Target text height in pixels (default 24)
+    #[serde(default = "default_target_text_height")]
+    pub target_height: f32,
+    #[serde(default)]
+    pub filter: ResampleFilter,
+}
+
+fn default_target_text_height() -> f32 {
+    DEFAULT_TARGET_TEXT_HEIGHT
+}
+
+impl PreprocessStep for NormalizeScale {
+    fn name(&self) -> &'static str {
+        "normalizeScale"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        check_range("target text height", self.target_height, 8.0, 100.0)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let Some(text_height) = estimate_text_height(&ctx.image) else {
+            ctx.trace.decision(
+                "normalizeScale",
+                "too few text components → unchanged".to_string(),
+            );
+            return Ok(());
+        };
+        let factor = normalization_factor(
+            text_height,
+            self.target_height,
+            ctx.image.width(),
+            ctx.image.height(),
+        );
+        ctx.trace.decision(
+            "normalizeScale",
+            format!(
+                "text height {:.0}px, target {:.0}px → scale {:.2}",
+                text_height, self.target_height, factor
+            ),
+        );
+        let width = ctx.image.width();
+        let (scaled, transform) = rescale(&ctx.image, factor, self.filter);
+        ctx.scale_factor *= scaled.width() as f32 / width as f32;
+        ctx.image = scaled;
+        ctx.mapper.extend(transform);
+        Ok(())
+    }
+}
+
+/// Invert light-on-dark regions so text is dark on light
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NormalizePolarity {}
+
+impl PreprocessStep for NormalizePolarity {
+    fn name(&self) -> &'static str {
+        "normalizePolarity"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let (normalized, detection) = normalize_polarity(&ctx.image);
+        let action = match detection.polarity {
+            Polarity::Normal => "unchanged",
+            Polarity::Inverted => "inverted image",
+            Polarity::Mixed => "inverted dark regions",
+        };
+        ctx.trace.decision(
+            "normalizePolarity",
+            format!(
+                "{:.0}% of image light-on-dark → {}",
+                detection.dark_fraction * 100.0,
+                action
+            ),
+        );
+        ctx.image = normalized;
+        Ok(())
+    }
+}
+
+/// Fade out stamps, highlighter and colored lines
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorDropout {
+    #[serde(default)]
+    pub mode: DropoutMode,
+    /// Colors to drop or keep, by name or as `{ from, to }` in degrees;
+    /// unused in auto mode
+    #[serde(default)]
+    pub hues: Vec<HueSpec>,
+}
+
+impl PreprocessStep for ColorDropout {
+    fn name(&self) -> &'static str {
+        "colorDropout"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.mode == DropoutMode::Drop && self.hues.is_empty() {
+            return Err("Color dropout in drop mode needs at least one hue".to_string());
+        }
+        self.hues.iter().try_for_each(HueSpec::validate)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let (dropped, report) = drop_colors(&ctx.image, self.mode, &self.hues);
+        let ranges = || {
+            self.hues
+                .iter()
+                .map(|hue| {
+                    let range = hue.range();
+                    format!("{:.0}-{:.0}°", range.from, range.to)
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let kept = match (self.mode, report.ink_hue) {
+            (DropoutMode::Auto, Some(hue)) => format!("ink hue {:.0}°", hue),
+            (DropoutMode::Auto, None) => "neutral ink".to_string(),
+            (DropoutMode::Drop, _) => format!("drop {}", ranges()),
+            (DropoutMode::Keep, _) => format!("keep {}", ranges()),
+        };
+        ctx.trace.decision(
+            "colorDropout",
+            format!(
+                "{} → dropped {:.1}% of pixels",
+                kept,
+                report.dropped_fraction * 100.0
+            ),
+        );
+        ctx.image = dropped;
+        Ok(())
+    }
+}
+
+/// Flatten shadows and lighting gradients
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizeBackground {
+    #[serde(default)]
+    pub method: BackgroundMethod,
+    #[serde(default)]
+    pub mode: FlattenMode,
+    /// Background filter size in pixels; derived from the text height when
+    /// unset
+    #[serde(default)]
+    pub kernel_size: Option<u32>,
+}
+
+impl PreprocessStep for NormalizeBackground {
+    fn name(&self) -> &'static str {
+        "normalizeBackground"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self.kernel_size {
+            Some(size) => check_range("background kernel size", size as f32, 3.0, 1000.0),
+            None => Ok(()),
+        }
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let (flattened, report) =
+            normalize_background(&ctx.image, self.method, self.mode, self.kernel_size);
+        ctx.trace.decision(
+            "normalizeBackground",
+            format!(
+                "unevenness {:.0}, {:?} kernel {}px → {:?}",
+                report.unevenness, self.method, report.kernel_size, self.mode
+            ),
+        );
+        ctx.image = flattened;
+        Ok(())
+    }
+}
+
+/// Erase table and form lines, optionally keeping them as a grid
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveLines {
+    /// Shortest line in pixels; four character heights when unset
+    #[serde(default)]
+    pub min_length: Option<u32>,
+    /// Return the erased lines with the OCR result
+    #[serde(default)]
+    pub return_grid: bool,
+}
+
+impl PreprocessStep for RemoveLines {
+    fn name(&self) -> &'static str {
+        "removeLines"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self.min_length {
+            Some(length) => check_range("minimum line length", length as f32, 5.0, 10000.0),
+            None => Ok(()),
+        }
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let (cleaned, removal) = remove_lines(&ctx.image, self.min_length);
+        ctx.trace.decision(
+            "removeLines",
+            format!(
+                "min length {}px → erased {} horizontal, {} vertical line(s)",
+                removal.min_length,
+                removal.grid.horizontal.len(),
+                removal.grid.vertical.len()
+            ),
+        );
+        if self.return_grid {
+            ctx.table_grid = Some(removal.grid.to_source(&ctx.mapper));
+        }
+        ctx.image = cleaned;
+        Ok(())
+    }
+}
+
+/// Edge-preserving noise reduction
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BilateralFilter {}
+
+impl PreprocessStep for BilateralFilter {
+    fn name(&self) -> &'static str {
+        "bilateralFilter"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        ctx.image = apply_bilateral_filter(&ctx.image);
+        Ok(())
+    }
+}
+
+/// Gaussian noise reduction
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GaussianBlur {
+    pub sigma: f32,
+}
+
+impl PreprocessStep for GaussianBlur {
+    fn name(&self) -> &'static str {
+        "gaussianBlur"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        check_range("blur sigma", self.sigma, 0.0, 10.0)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        ctx.image = apply_gaussian_blur(&ctx.image, self.sigma);
+        Ok(())
+    }
+}
+
+/// Brightness adjustment
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Brightness {
+    /// Offset from -1.0 to 1.0
+    pub amount: f32,
+}
+
+impl PreprocessStep for Brightness {
+    fn name(&self) -> &'static str {
+        "brightness"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        check_range("brightness", self.amount, -1.0, 1.0)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        ctx.image = adjust_brightness(&ctx.image, self.amount);
+        Ok(())
+    }
+}
+
+/// Contrast adjustment
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Contrast {
+    /// Contrast factor; 1.0 leaves the image unchanged
+    pub factor: f32,
+}
+
+impl PreprocessStep for Contrast {
+    fn name(&self) -> &'static str {
+        "contrast"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        check_range("contrast", self.factor, 0.1, 5.0)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        ctx.image = adjust_contrast(&ctx.image, self.factor);
+        Ok(())
+    }
+}
+
+/// Unsharp-mask sharpening
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Sharpen {
+    /// Sharpness factor; 1.0 leaves the image unchanged
+    pub amount: f32,
+}
+
+impl PreprocessStep for Sharpen {
+    fn name(&self) -> &'static str {
+        "sharpen"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        check_range("sharpness", self.amount, 0.0, 5.0)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        ctx.image = adjust_sharpness(&ctx.image, self.amount);
+        Ok(())
+    }
+}
+
+/// Contrast-limited adaptive histogram equalization
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Clahe {
+    /// Tiles per side
+    #[serde(default = "default_clahe_tile_grid")]
+    pub tile_grid: u32,
+    /// Clip limit relative to the mean histogram bin
+    #[serde(default = "default_clahe_clip_limit")]
+    pub clip_limit: f32,
+}
+
+fn default_clahe_tile_grid() -> u32 {
+    DEFAULT_CLAHE_TILE_GRID
+}
+
+fn default_clahe_clip_limit() -> f32 {
+    DEFAULT_CLAHE_CLIP_LIMIT
+}
+
+impl PreprocessStep for Clahe {
+    fn name(&self) -> &'static str {
+        "clahe"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_clahe_params(self.tile_grid, self.clip_limit)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        ctx.image = apply_clahe(&ctx.image, self.tile_grid, self.clip_limit)?;
+        Ok(())
+    }
+}
+
+/// Morphological operation with a 3×3 kernel
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Morphology {
+    pub operation: MorphologyOperation,
+}
+
+impl PreprocessStep for Morphology {
+    fn name(&self) -> &'static str {
+        "morphology"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        if self.operation != MorphologyOperation::None {
+            ctx.image = apply_morphology(&ctx.image, self.operation);
+        }
+        Ok(())
+    }
+}
+
+/// Global or local thresholding to black and white
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Binarize {
+    pub method: BinarizationMethod,
+    /// Local threshold window in pixels (default depends on the method)
+    #[serde(default)]
+    pub window: Option<u32>,
+    /// Local threshold sensitivity `k` (default depends on the method)
+    #[serde(default)]
+    pub k: Option<f32>,
+    /// Sauvola dynamic range `R` (default 128)
+    #[serde(default)]
+    pub r: Option<f32>,
+}
+
+impl PreprocessStep for Binarize {
+    fn name(&self) -> &'static str {
+        "binarize"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(window) = self.window {
+            if window < 3 {
+                return Err(format!(
+                    "Invalid threshold window: {} (expected at least 3)",
+                    window
+                ));
+            }
+        }
+        if let Some(k) = self.k {
+            check_range("threshold k", k, -1.0, 1.0)?;
+        }
+        if let Some(r) = self.r {
+            check_range("Sauvola R", r, 1.0, 255.0)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        ctx.image = match self.method {
+            BinarizationMethod::None => return Ok(()),
+            BinarizationMethod::Adaptive => apply_adaptive_threshold(&ctx.image)?,
+            BinarizationMethod::Otsu => apply_otsu_threshold(&ctx.image)?,
+            BinarizationMethod::Mean => apply_mean_threshold(&ctx.image)?,
+            method => {
+                let Some(local_method) = method.local_method() else {
+                    return Ok(());
+                };
+                let local_params = LocalThresholdParams {
+                    window_size: self
+                        .window
+                        .unwrap_or_else(|| local_method.default_window(ctx.image.width())),
+                    k: self.k.unwrap_or_else(|| local_method.default_k()),
+                    r: self.r.unwrap_or(DEFAULT_SAUVOLA_R),
+                };
+                apply_local_threshold(&ctx.image, local_method, &local_params)?
+            }
+        };
+        Ok(())
+    }
+}
+
+/// Remove specks and border blobs and fill pinholes by connected component
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Despeckle {
+    /// Specks are smaller than a square of this share of the character
+    /// height (default 0.1)
+    #[serde(default = "default_speck_size")]
+    pub speck_size: f32,
+    /// Fill holes of speck size inside strokes
+    #[serde(default = "default_true")]
+    pub fill_holes: bool,
+    /// Remove components touching the image border; off by default, as
+    /// text running off a cropped page would go with them
+    #[serde(default)]
+    pub remove_border: bool,
+}
+
+fn default_speck_size() -> f32 {
+    DEFAULT_SPECK_SIZE
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl PreprocessStep for Despeckle {
+    fn name(&self) -> &'static str {
+        "despeckle"
+    }
+
+    fn params(&self) -> serde_json::Value {
+        to_params(self)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        check_range("speck size", self.speck_size, 0.0, 1.0)
+    }
+
+    fn apply(&self, ctx: &mut StepContext) -> Result<(), String> {
+        let options = DespeckleOptions {
+            speck_size: self.speck_size,
+            fill_holes: self.fill_holes,
+            remove_border: self.remove_border,
+        };
+        let (cleaned, report) = despeckle(&ctx.image, &options);
+        let basis = match report.character_height {
+            Some(height) => format!("character height {:.0}px", height),
+            None => "no character height".to_string(),
+        };
+        ctx.trace.decision(
+            "despeckle",
+            format!(
+                "{} → min area {}px: removed {} speck(s), {} border component(s), filled {} hole(s)",
+                basis,
+                report.min_area,
+                report.specks_removed,
+                report.border_components_removed,
+                report.holes_filled
+            ),
+        );
+        ctx.image = cleaned;
+        Ok(())
+    }
+}
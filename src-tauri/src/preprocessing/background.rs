@@ -0,0 +1,214 @@
+//! Background estimation and illumination flattening
+//!
+//! Photos of paper under a desk lamp have gradients and shadows, so a
+//! single global threshold turns the dim half of the page black. The
+//! background is estimated with a filter larger than the text strokes
+//! (a morphological closing or a median), and the image is divided by it
+//! or has it subtracted, leaving a flat white page with the text intact.
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GrayImage, Luma, Rgba};
+use imageproc::filter::{gaussian_blur_f32, median_filter};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::components::{estimate_character_height, find_components, text_mask};
+
+/// Longest side of the downscaled copy the background is estimated on
+const WORK_SIZE: u32 = 512;
+
+/// Smallest background kernel, in source pixels
+const MIN_KERNEL_SIZE: u32 = 15;
+
+/// Unevenness (0-100) above which adaptive mode flattens the background
+pub const UNEVEN_ILLUMINATION_THRESHOLD: f32 = 25.0;
+
+/// Background estimation filter
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BackgroundMethod {
+    /// Grayscale closing (max then min filter); removes dark text entirely
+    #[default]
+    Closing,
+    /// Median filter; more tolerant of speckle and bright glare
+    Median,
+}
+
+/// How the estimated background is removed
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FlattenMode {
+    /// Divide by the background; keeps stroke contrast proportional
+    #[default]
+    Divide,
+    /// Subtract the background's darkening; gentler on faint text
+    Subtract,
+}
+
+/// Result of background normalization
+#[derive(Debug, Clone, Copy)]
+pub struct BackgroundNormalization {
+    /// Kernel size used for the estimate, in source pixels
+    pub kernel_size: u32,
+    /// Illumination unevenness before flattening (0-100)
+    pub unevenness: f32,
+}
+
+/// Running maximum or minimum over a `2 * radius + 1` window in one axis
+fn extremum_filter(img: &GrayImage, radius: u32, horizontal: bool, max: bool) -> GrayImage {
+    let (width, height) = img.dimensions();
+    let mut output = GrayImage::new(width, height);
+    let radius = radius as i64;
+    for y in 0..height {
+        for x in 0..width {
+            let (center, len) = if horizontal {
+                (x as i64, width as i64)
+            } else {
+                (y as i64, height as i64)
+            };
+            let start = (center - radius).max(0);
+            let end = (center + radius).min(len - 1);
+            let values = (start..=end).map(|i| {
+                let (sx, sy) = if horizontal {
+                    (i as u32, y)
+                } else {
+                    (x, i as u32)
+                };
+                img.get_pixel(sx, sy).0[0]
+            });
+            let value = if max { values.max() } else { values.min() };
+            output.put_pixel(x, y, Luma([value.unwrap_or(0)]));
+        }
+    }
+    output
+}
+
+/// Background of a grayscale image, estimated at reduced resolution
+fn background_map(gray: &GrayImage, method: BackgroundMethod, kernel_size: u32) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    let ratio = (WORK_SIZE as f32 / width.max(height) as f32).min(1.0);
+    let small = if ratio < 1.0 {
+        imageops::resize(
+            gray,
+            ((width as f32 * ratio).round() as u32).max(1),
+            ((height as f32 * ratio).round() as u32).max(1),
+            FilterType::Triangle,
+        )
+    } else {
+        gray.clone()
+    };
+
+    let radius = ((kernel_size as f32 * ratio / 2.0).round() as u32).max(1);
+    let background = match method {
+        BackgroundMethod::Closing => {
+            let dilated = extremum_filter(&small, radius, true, true);
+            let dilated = extremum_filter(&dilated, radius, false, true);
+            let closed = extremum_filter(&dilated, radius, true, false);
+            extremum_filter(&closed, radius, false, false)
+        }
+        BackgroundMethod::Median => median_filter(&small, radius, radius),
+    };
+
+    // The filters leave blocky plateaus; smooth them before upscaling
+    gaussian_blur_f32(&background, (radius as f32 / 2.0).max(1.0))
+}
+
+/// Spread between the brightest and darkest background, as a percentage
+/// of the brightest (2nd and 98th percentiles, so specks don't count)
+fn unevenness(background: &GrayImage) -> f32 {
+    let mut values: Vec<u8> = background.pixels().map(|p| p.0[0]).collect();
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_unstable();
+    let low = values[values.len() * 2 / 100] as f32;
+    let high = values[values.len() * 98 / 100] as f32;
+    ((high - low) / high.max(1.0) * 100.0).clamp(0.0, 100.0)
+}
+
+/// Kernel size that removes text strokes: a few character heights, or a
+/// share of the image size when no text was found
+fn default_kernel_size(gray: &GrayImage) -> u32 {
+    let (width, height) = gray.dimensions();
+    let (_, components) = find_components(&text_mask(gray));
+    let kernel = match estimate_character_height(&components, height) {
+        Some(char_height) => (char_height * 3.0).round() as u32,
+        None => width.max(height) / 20,
+    };
+    kernel.max(MIN_KERNEL_SIZE)
+}
+
+/// Measure how unevenly an image is lit
+///
+/// # Arguments
+/// * `img` - The input image
+///
+/// # Returns
+/// Unevenness from 0 (flat background) to 100; values above
+/// [`UNEVEN_ILLUMINATION_THRESHOLD`] indicate shadows or gradients
+pub fn measure_illumination_unevenness(img: &DynamicImage) -> f32 {
+    let gray = img.to_luma8();
+    let kernel = (gray.width().max(gray.height()) / 20).max(MIN_KERNEL_SIZE);
+    unevenness(&background_map(&gray, BackgroundMethod::Closing, kernel))
+}
+
+/// Flatten uneven illumination while preserving text strokes
+///
+/// Steps:
+/// 1. Estimate the background on a downscaled grayscale copy with a
+///    kernel larger than the text strokes
+/// 2. Smooth and upscale the estimate to full resolution
+/// 3. Divide every channel by the background (or add back its darkening)
+///
+/// Assumes dark text on a light background; run polarity normalization
+/// first for dark-mode images.
+///
+/// # Arguments
+/// * `img` - The input image
+/// * `method` - Background estimation filter
+/// * `mode` - How the background is removed
+/// * `kernel_size` - Filter size in pixels; derived from the character
+///   height when `None`
+///
+/// # Returns
+/// The flattened image and the kernel size and unevenness measured
+pub fn normalize_background(
+    img: &DynamicImage,
+    method: BackgroundMethod,
+    mode: FlattenMode,
+    kernel_size: Option<u32>,
+) -> (DynamicImage, BackgroundNormalization) {
+    let gray = img.to_luma8();
+    let (width, height) = gray.dimensions();
+    let kernel_size = kernel_size.unwrap_or_else(|| default_kernel_size(&gray));
+
+    let small = background_map(&gray, method, kernel_size);
+    let report = BackgroundNormalization {
+        kernel_size,
+        unevenness: unevenness(&small),
+    };
+    let background = imageops::resize(&small, width, height, FilterType::Triangle);
+
+    let mut rgba = img.to_rgba8();
+    for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+        let level = background.get_pixel(x, y).0[0].max(1) as f32;
+        let [r, g, b, a] = pixel.0;
+        let flatten = |channel: u8| -> u8 {
+            let value = match mode {
+                FlattenMode::Divide => channel as f32 * 255.0 / level,
+                FlattenMode::Subtract => channel as f32 + (255.0 - level),
+            };
+            value.round().clamp(0.0, 255.0) as u8
+        };
+        *pixel = Rgba([flatten(r), flatten(g), flatten(b), a]);
+    }
+
+    log::debug!(
+        "[Background] {:?} kernel {}px, unevenness {:.1}",
+        method,
+        kernel_size,
+        report.unevenness
+    );
+
+    (DynamicImage::ImageRgba8(rgba), report)
+}
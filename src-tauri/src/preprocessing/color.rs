@@ -0,0 +1,239 @@
+//! Color dropout
+//!
+//! Every later stage works on luminance, where yellow highlighter, red
+//! stamps and blue form lines turn into gray blobs that binarize as text.
+//! Pixels are classified by hue and saturation (HSV), and colored pixels
+//! that should not be read are replaced by their brightest channel, as if
+//! the page were lit with light of their own color, so they fade into the
+//! paper while dark ink underneath them stays dark.
+
+use image::{DynamicImage, Rgba};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::binarization::calculate_otsu_threshold;
+
+/// Pixels less saturated than this are neutral (black, gray or white)
+const MIN_SATURATION: f32 = 0.25;
+
+/// Pixels darker than this are ink whatever their hue
+const MIN_VALUE: f32 = 0.15;
+
+/// Width of the hue histogram bins used to find the ink color, in degrees
+const HUE_BIN: f32 = 10.0;
+
+/// Half-width of the hue range kept around the detected ink color
+const INK_HUE_TOLERANCE: f32 = 20.0;
+
+/// Which colored pixels are dropped
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DropoutMode {
+    /// Keep the dominant ink color and drop every other color
+    #[default]
+    Auto,
+    /// Drop colors in the given hue ranges
+    Drop,
+    /// Drop every color outside the given hue ranges; neutral ink is kept
+    Keep,
+}
+
+/// Common colors by name
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NamedHue {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Cyan,
+    Blue,
+    Magenta,
+}
+
+/// A hue range in degrees; wraps through 0 when `from` exceeds `to`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct HueRange {
+    pub from: f32,
+    pub to: f32,
+}
+
+impl HueRange {
+    fn contains(&self, hue: f32) -> bool {
+        if self.from <= self.to {
+            (self.from..=self.to).contains(&hue)
+        } else {
+            hue >= self.from || hue <= self.to
+        }
+    }
+
+    fn around(center: f32, tolerance: f32) -> Self {
+        Self {
+            from: (center - tolerance).rem_euclid(360.0),
+            to: (center + tolerance).rem_euclid(360.0),
+        }
+    }
+}
+
+/// A hue range given by color name or in degrees
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum HueSpec {
+    Named(NamedHue),
+    Range(HueRange),
+}
+
+impl HueSpec {
+    /// The hue range in degrees
+    pub fn range(&self) -> HueRange {
+        let (from, to) = match *self {
+            HueSpec::Named(NamedHue::Red) => (345.0, 15.0),
+            HueSpec::Named(NamedHue::Orange) => (15.0, 45.0),
+            HueSpec::Named(NamedHue::Yellow) => (45.0, 70.0),
+            HueSpec::Named(NamedHue::Green) => (70.0, 170.0),
+            HueSpec::Named(NamedHue::Cyan) => (170.0, 200.0),
+            HueSpec::Named(NamedHue::Blue) => (200.0, 260.0),
+            HueSpec::Named(NamedHue::Magenta) => (260.0, 345.0),
+            HueSpec::Range(range) => return range,
+        };
+        HueRange { from, to }
+    }
+
+    /// Check that a range in degrees lies within 0-360
+    pub fn validate(&self) -> Result<(), String> {
+        let range = self.range();
+        if (0.0..=360.0).contains(&range.from) && (0.0..=360.0).contains(&range.to) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Invalid hue range: {} to {} (expected 0 to 360)",
+                range.from, range.to
+            ))
+        }
+    }
+}
+
+/// Result of color dropout
+#[derive(Debug, Clone, Copy)]
+pub struct ColorDropoutReport {
+    /// Share of the pixels that were dropped (0.0-1.0)
+    pub dropped_fraction: f32,
+    /// Hue of the ink kept in auto mode; `None` when the ink is neutral or
+    /// the mode is not auto
+    pub ink_hue: Option<f32>,
+}
+
+/// Hue in degrees, saturation and value (0.0-1.0) of an RGB pixel
+fn hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let saturation = if max > 0.0 { delta / max } else { 0.0 };
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, saturation, max)
+}
+
+/// Hue of a pixel, or `None` for neutral and very dark pixels
+fn chromatic_hue(pixel: &Rgba<u8>) -> Option<f32> {
+    let [r, g, b, _] = pixel.0;
+    let (hue, saturation, value) = hsv(r, g, b);
+    (saturation >= MIN_SATURATION && value >= MIN_VALUE).then_some(hue)
+}
+
+/// Hue of the dominant ink color, or `None` when most ink is neutral
+///
+/// Ink is the dark side of the Otsu threshold.
+fn detect_ink_hue(img: &DynamicImage) -> Option<f32> {
+    let gray = img.to_luma8();
+    let threshold = calculate_otsu_threshold(&gray);
+
+    let bins = (360.0 / HUE_BIN) as usize;
+    let mut histogram = vec![0u32; bins];
+    let mut neutral = 0u32;
+    for (pixel, luma) in img.to_rgba8().pixels().zip(gray.pixels()) {
+        if luma.0[0] > threshold {
+            continue;
+        }
+        match chromatic_hue(pixel) {
+            Some(hue) => histogram[(hue / HUE_BIN) as usize % bins] += 1,
+            None => neutral += 1,
+        }
+    }
+
+    let colored: u32 = histogram.iter().sum();
+    if colored <= neutral {
+        return None;
+    }
+    let (bin, _) = histogram
+        .iter()
+        .enumerate()
+        .max_by_key(|&(_, &count)| count)?;
+    Some((bin as f32 + 0.5) * HUE_BIN)
+}
+
+/// Fade colored marks into the background
+///
+/// # Arguments
+/// * `img` - The input image
+/// * `mode` - Whether to drop or keep the given hues, or detect the ink
+///   color automatically
+/// * `hues` - Hue ranges for the drop and keep modes; ignored in auto mode
+///
+/// # Returns
+/// The image with dropped colors faded out, and the share of pixels
+/// dropped
+pub fn drop_colors(
+    img: &DynamicImage,
+    mode: DropoutMode,
+    hues: &[HueSpec],
+) -> (DynamicImage, ColorDropoutReport) {
+    let (keep, ranges, ink_hue) = match mode {
+        DropoutMode::Drop => (false, hues.iter().map(HueSpec::range).collect(), None),
+        DropoutMode::Keep => (true, hues.iter().map(HueSpec::range).collect(), None),
+        DropoutMode::Auto => {
+            let ink_hue = detect_ink_hue(img);
+            let ranges: Vec<HueRange> = ink_hue
+                .map(|hue| HueRange::around(hue, INK_HUE_TOLERANCE))
+                .into_iter()
+                .collect();
+            (true, ranges, ink_hue)
+        }
+    };
+
+    let mut rgba = img.to_rgba8();
+    let mut dropped = 0usize;
+    for pixel in rgba.pixels_mut() {
+        let Some(hue) = chromatic_hue(pixel) else {
+            continue;
+        };
+        if ranges.iter().any(|range| range.contains(hue)) == keep {
+            continue;
+        }
+        let [r, g, b, a] = pixel.0;
+        let value = r.max(g).max(b);
+        *pixel = Rgba([value, value, value, a]);
+        dropped += 1;
+    }
+
+    let report = ColorDropoutReport {
+        dropped_fraction: dropped as f32 / rgba.pixels().len().max(1) as f32,
+        ink_hue,
+    };
+    log::debug!(
+        "[ColorDropout] {:?}: dropped {:.1}% of pixels, ink hue {:?}",
+        mode,
+        report.dropped_fraction * 100.0,
+        report.ink_hue
+    );
+
+    (DynamicImage::ImageRgba8(rgba), report)
+}
@@ -0,0 +1,156 @@
+//! Connected-component analysis
+//!
+//! Labels blobs on a binary foreground mask and collects their bounding
+//! boxes and areas, used to estimate character size.
+
+use image::{GrayImage, ImageBuffer, Luma};
+use imageproc::region_labelling::{connected_components, Connectivity};
+
+use crate::binarization::calculate_otsu_threshold;
+
+/// Mask value of foreground (text) pixels
+pub const FOREGROUND: u8 = 255;
+
+/// Fewest character-like components needed for a size estimate
+const MIN_CHARACTER_COMPONENTS: usize = 10;
+
+/// Label image: 0 is background, component `i` has label `i + 1`
+pub type LabelImage = ImageBuffer<Luma<u32>, Vec<u32>>;
+
+/// Bounding box and pixel count of one connected component
+#[derive(Debug, Clone, Copy)]
+pub struct Component {
+    pub left: u32,
+    pub top: u32,
+    /// Inclusive right edge
+    pub right: u32,
+    /// Inclusive bottom edge
+    pub bottom: u32,
+    pub area: u32,
+}
+
+impl Component {
+    pub fn width(&self) -> u32 {
+        self.right - self.left + 1
+    }
+
+    pub fn height(&self) -> u32 {
+        self.bottom - self.top + 1
+    }
+
+    /// Plausible size and shape for a character or character fragment
+    fn is_character_like(&self, image_height: u32) -> bool {
+        let (width, height) = (self.width(), self.height());
+        height >= 3
+            && self.area >= 4
+            && height <= image_height / 2
+            && width <= height * 8
+            && height <= width * 12
+    }
+}
+
+/// Foreground mask of the text in a grayscale image
+///
+/// Pixels on the minority side of the Otsu threshold are foreground, so
+/// both dark-on-light and light-on-dark text give a text mask.
+///
+/// # Arguments
+/// * `gray` - A grayscale image
+///
+/// # Returns
+/// A mask with text pixels set to [`FOREGROUND`] and the rest 0
+pub fn text_mask(gray: &GrayImage) -> GrayImage {
+    let threshold = calculate_otsu_threshold(gray);
+    let dark = gray.pixels().filter(|p| p.0[0] <= threshold).count();
+    let dark_is_text = dark * 2 <= gray.pixels().len();
+
+    let mut mask = GrayImage::new(gray.width(), gray.height());
+    for (x, y, pixel) in gray.enumerate_pixels() {
+        if (pixel.0[0] <= threshold) == dark_is_text {
+            mask.put_pixel(x, y, Luma([FOREGROUND]));
+        }
+    }
+    mask
+}
+
+/// Label 8-connected foreground components of a mask
+///
+/// # Arguments
+/// * `mask` - Binary mask with foreground pixels set to [`FOREGROUND`]
+///
+/// # Returns
+/// The label image and the components, indexed by label - 1
+pub fn find_components(mask: &GrayImage) -> (LabelImage, Vec<Component>) {
+    find_components_with(mask, Connectivity::Eight)
+}
+
+/// Label foreground components of a mask with the given connectivity
+///
+/// Background regions of an 8-connected foreground need 4-connectivity:
+/// a hole closed only by a diagonal step is still enclosed.
+///
+/// # Arguments
+/// * `mask` - Binary mask with foreground pixels set to [`FOREGROUND`]
+/// * `connectivity` - Which neighbours join a component
+///
+/// # Returns
+/// The label image and the components, indexed by label - 1
+pub fn find_components_with(
+    mask: &GrayImage,
+    connectivity: Connectivity,
+) -> (LabelImage, Vec<Component>) {
+    let labels = connected_components(mask, connectivity, Luma([0u8]));
+
+    let mut components: Vec<Component> = Vec::new();
+    for (x, y, label) in labels.enumerate_pixels() {
+        let label = label.0[0] as usize;
+        if label == 0 {
+            continue;
+        }
+        if components.len() < label {
+            components.resize(
+                label,
+                Component {
+                    left: u32::MAX,
+                    top: u32::MAX,
+                    right: 0,
+                    bottom: 0,
+                    area: 0,
+                },
+            );
+        }
+        let component = &mut components[label - 1];
+        component.left = component.left.min(x);
+        component.top = component.top.min(y);
+        component.right = component.right.max(x);
+        component.bottom = component.bottom.max(y);
+        component.area += 1;
+    }
+
+    (labels, components)
+}
+
+/// Median height of the character-like components
+///
+/// Lines, large graphics and single-pixel noise are ignored.
+///
+/// # Arguments
+/// * `components` - Components from [`find_components`]
+/// * `image_height` - Height of the labelled image
+///
+/// # Returns
+/// The dominant character height in pixels, or `None` when too few
+/// characters were found
+pub fn estimate_character_height(components: &[Component], image_height: u32) -> Option<f32> {
+    let mut heights: Vec<u32> = components
+        .iter()
+        .filter(|component| component.is_character_like(image_height))
+        .map(Component::height)
+        .collect();
+    if heights.len() < MIN_CHARACTER_COMPONENTS {
+        return None;
+    }
+    let middle = heights.len() / 2;
+    let (_, median, _) = heights.select_nth_unstable(middle);
+    Some(*median as f32)
+}
@@ -0,0 +1,216 @@
+//! Connected-component despeckling
+//!
+//! Blurring and a fixed opening thin every stroke to remove noise. Working
+//! on whole connected components of the binarized page instead, dust and
+//! scanner specks are dropped by their size relative to the text, pinholes
+//! inside strokes are filled, and blobs running off the page edge (binder
+//! holes, shadows of the scanner lid) are removed, leaving the characters
+//! untouched.
+
+use image::{DynamicImage, GrayImage, Luma};
+use imageproc::region_labelling::Connectivity;
+
+use super::components::{
+    estimate_character_height, find_components, find_components_with, Component, FOREGROUND,
+};
+use crate::binarization::calculate_otsu_threshold;
+
+/// Default speck size, as a share of the character height
+pub const DEFAULT_SPECK_SIZE: f32 = 0.1;
+
+/// Speck area in pixels when the character height cannot be estimated
+const FALLBACK_SPECK_AREA: u32 = 3;
+
+/// Which cleanups to run
+#[derive(Debug, Clone, Copy)]
+pub struct DespeckleOptions {
+    /// Components smaller than a square of this share of the character
+    /// height are specks; holes of the same size are filled
+    pub speck_size: f32,
+    /// Fill background holes smaller than a speck inside strokes
+    pub fill_holes: bool,
+    /// Remove components touching the image border
+    pub remove_border: bool,
+}
+
+/// What despeckling removed
+#[derive(Debug, Clone, Copy)]
+pub struct DespeckleReport {
+    /// Area threshold used, in pixels
+    pub min_area: u32,
+    /// Character height the threshold was derived from, if one was found
+    pub character_height: Option<f32>,
+    pub specks_removed: usize,
+    pub holes_filled: usize,
+    pub border_components_removed: usize,
+}
+
+/// Whether a component touches the edge of a `width` x `height` image
+fn touches_border(component: &Component, width: u32, height: u32) -> bool {
+    component.left == 0
+        || component.top == 0
+        || component.right + 1 == width
+        || component.bottom + 1 == height
+}
+
+/// Remove specks and border blobs and fill pinholes in a binarized image
+///
+/// Dark pixels are text; the output is a black-on-white grayscale image.
+///
+/// # Arguments
+/// * `img` - The binarized input image, dark text on a light background
+/// * `options` - Speck size and which cleanups to run
+///
+/// # Returns
+/// The cleaned image and counts of what was removed
+pub fn despeckle(
+    img: &DynamicImage,
+    options: &DespeckleOptions,
+) -> (DynamicImage, DespeckleReport) {
+    let gray = img.to_luma8();
+    let (width, height) = gray.dimensions();
+    let threshold = calculate_otsu_threshold(&gray);
+
+    let mut mask = GrayImage::new(width, height);
+    for (x, y, pixel) in gray.enumerate_pixels() {
+        if pixel.0[0] <= threshold {
+            mask.put_pixel(x, y, Luma([FOREGROUND]));
+        }
+    }
+
+    let (labels, components) = find_components(&mask);
+    let character_height = estimate_character_height(&components, height);
+    let min_area = match character_height {
+        Some(char_height) => (char_height * options.speck_size).powi(2).round() as u32,
+        None => FALLBACK_SPECK_AREA,
+    };
+
+    let mut report = DespeckleReport {
+        min_area,
+        character_height,
+        specks_removed: 0,
+        holes_filled: 0,
+        border_components_removed: 0,
+    };
+
+    // Decide per component, then erase in one pass over the labels
+    let removed: Vec<bool> = components
+        .iter()
+        .map(|component| {
+            if options.remove_border && touches_border(component, width, height) {
+                report.border_components_removed += 1;
+                true
+            } else if component.area < min_area {
+                report.specks_removed += 1;
+                true
+            } else {
+                false
+            }
+        })
+        .collect();
+    for (x, y, label) in labels.enumerate_pixels() {
+        let label = label.0[0] as usize;
+        if label > 0 && removed[label - 1] {
+            mask.put_pixel(x, y, Luma([0]));
+        }
+    }
+
+    // Holes are background components enclosed by text; the background is
+    // 4-connected, the dual of the 8-connected text, so a stroke meeting
+    // itself diagonally still closes a hole
+    if options.fill_holes && min_area > 0 {
+        let mut background = GrayImage::new(width, height);
+        for (x, y, pixel) in mask.enumerate_pixels() {
+            if pixel.0[0] != FOREGROUND {
+                background.put_pixel(x, y, Luma([FOREGROUND]));
+            }
+        }
+        let (hole_labels, holes) = find_components_with(&background, Connectivity::Four);
+        let filled: Vec<bool> = holes
+            .iter()
+            .map(|hole| hole.area < min_area && !touches_border(hole, width, height))
+            .collect();
+        report.holes_filled = filled.iter().filter(|&&fill| fill).count();
+        for (x, y, label) in hole_labels.enumerate_pixels() {
+            let label = label.0[0] as usize;
+            if label > 0 && filled[label - 1] {
+                mask.put_pixel(x, y, Luma([FOREGROUND]));
+            }
+        }
+    }
+
+    let mut output = GrayImage::new(width, height);
+    for (x, y, pixel) in mask.enumerate_pixels() {
+        let value = if pixel.0[0] == FOREGROUND { 0 } else { 255 };
+        output.put_pixel(x, y, Luma([value]));
+    }
+
+    log::debug!(
+        "[Despeckle] min area {}px: {} speck(s), {} hole(s), {} border component(s)",
+        report.min_area,
+        report.specks_removed,
+        report.holes_filled,
+        report.border_components_removed
+    );
+
+    (DynamicImage::ImageLuma8(output), report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS: DespeckleOptions = DespeckleOptions {
+        speck_size: DEFAULT_SPECK_SIZE,
+        fill_holes: true,
+        remove_border: false,
+    };
+
+    /// White page with the given pixels black
+    fn page(black: &[(u32, u32)]) -> DynamicImage {
+        let mut img = GrayImage::from_pixel(20, 20, Luma([255]));
+        for &(x, y) in black {
+            img.put_pixel(x, y, Luma([0]));
+        }
+        DynamicImage::ImageLuma8(img)
+    }
+
+    fn is_black(img: &DynamicImage, x: u32, y: u32) -> bool {
+        img.to_luma8().get_pixel(x, y).0[0] == 0
+    }
+
+    #[test]
+    fn fills_hole_closed_by_diagonal_steps() {
+        // A pixel whose four neighbours are ink but whose diagonals are not
+        let img = page(&[(10, 9), (9, 10), (11, 10), (10, 11)]);
+        let (cleaned, report) = despeckle(&img, &OPTIONS);
+        assert_eq!(report.holes_filled, 1);
+        assert!(is_black(&cleaned, 10, 10));
+        assert!(!is_black(&cleaned, 9, 9));
+    }
+
+    #[test]
+    fn removes_specks() {
+        let img = page(&[(5, 5), (10, 9), (11, 9), (10, 10), (11, 10)]);
+        let (cleaned, report) = despeckle(&img, &OPTIONS);
+        assert_eq!(report.specks_removed, 1);
+        assert!(!is_black(&cleaned, 5, 5));
+        assert!(is_black(&cleaned, 10, 10));
+    }
+
+    #[test]
+    fn keeps_border_components_unless_asked() {
+        let img = page(&[(0, 5), (1, 5), (0, 6), (1, 6)]);
+        let (kept, report) = despeckle(&img, &OPTIONS);
+        assert_eq!(report.border_components_removed, 0);
+        assert!(is_black(&kept, 0, 5));
+
+        let options = DespeckleOptions {
+            remove_border: true,
+            ..OPTIONS
+        };
+        let (cleaned, report) = despeckle(&img, &options);
+        assert_eq!(report.border_components_removed, 1);
+        assert!(!is_black(&cleaned, 0, 5));
+    }
+}
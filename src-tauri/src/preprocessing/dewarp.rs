@@ -0,0 +1,329 @@
+//! Page curl dewarping
+//!
+//! Photos of open books have text lines that bend towards the spine,
+//! which neither deskew method can fix. Characters are chained into text
+//! lines, a quadratic curve is fitted to each line, and the vertical
+//! displacement of the curves is interpolated between lines to remap the
+//! image so the lines become straight. Runs after perspective correction
+//! and deskewing, once lines are roughly horizontal.
+
+use image::{DynamicImage, ImageBuffer, Rgba};
+use imageproc::geometric_transformations::{warp_into_with, Interpolation};
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::components::{estimate_character_height, find_components, text_mask, Component};
+use super::transform::ImageTransform;
+
+/// Fewest characters in a line used for fitting
+const MIN_LINE_COMPONENTS: usize = 8;
+
+/// Shortest line used for fitting, as a share of the image width
+const MIN_LINE_SPAN: f32 = 0.25;
+
+/// Fewest fitted lines needed to dewarp
+const MIN_LINES: usize = 2;
+
+/// Mean line curvature (in pixels) below which the page counts as flat
+const MIN_CURVATURE: f32 = 1.0;
+
+/// How much curvature dewarping removed
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DewarpReport {
+    /// Text lines the displacement field was fitted to
+    pub lines: usize,
+    /// Mean deviation of the text lines from straight, in pixels
+    pub curvature_before: f32,
+    /// The same measure on the dewarped image
+    pub curvature_after: f32,
+    /// Share of the curvature removed (0.0-1.0)
+    pub curvature_removed: f32,
+}
+
+/// A quadratic fitted to the centers of one text line
+struct FittedLine {
+    /// `y = c[0] + c[1] * x + c[2] * x²`
+    coefficients: [f64; 3],
+    left: f32,
+    right: f32,
+}
+
+impl FittedLine {
+    /// Curve height at `x`, held constant beyond the line's ends
+    fn y_at(&self, x: f32) -> f32 {
+        let x = x.clamp(self.left, self.right) as f64;
+        let [c0, c1, c2] = self.coefficients;
+        (c0 + c1 * x + c2 * x * x) as f32
+    }
+
+    /// Largest deviation of the curve from the chord between its ends
+    fn sagitta(&self) -> f32 {
+        let half_span = (self.right - self.left) as f64 / 2.0;
+        (self.coefficients[2].abs() * half_span * half_span) as f32
+    }
+}
+
+/// Vertical displacement mapping a dewarped image back to the curved one
+#[derive(Debug)]
+pub struct DisplacementField {
+    /// Height of each line at the reference column, in ascending order
+    line_y: Vec<f32>,
+    /// Per line, the offset of the curve from `line_y` at every column
+    offsets: Vec<Vec<f32>>,
+}
+
+impl DisplacementField {
+    fn new(mut lines: Vec<FittedLine>, width: u32, reference_x: f32) -> Self {
+        lines.sort_by(|a, b| a.y_at(reference_x).total_cmp(&b.y_at(reference_x)));
+        let line_y: Vec<f32> = lines.iter().map(|line| line.y_at(reference_x)).collect();
+        let offsets = lines
+            .iter()
+            .zip(&line_y)
+            .map(|(line, &y)| (0..width).map(|x| line.y_at(x as f32) - y).collect())
+            .collect();
+        Self { line_y, offsets }
+    }
+
+    /// Vertical offset at `(x, y)`, interpolated between the nearest lines
+    fn offset(&self, x: f32, y: f32) -> f32 {
+        let width = self.offsets[0].len();
+        let column = (x.max(0.0) as usize).min(width - 1);
+        let below = self.line_y.partition_point(|&line_y| line_y <= y);
+        if below == 0 {
+            return self.offsets[0][column];
+        }
+        if below == self.line_y.len() {
+            return self.offsets[below - 1][column];
+        }
+        let (y0, y1) = (self.line_y[below - 1], self.line_y[below]);
+        let t = if y1 > y0 { (y - y0) / (y1 - y0) } else { 0.0 };
+        let (d0, d1) = (self.offsets[below - 1][column], self.offsets[below][column]);
+        d0 + (d1 - d0) * t
+    }
+
+    /// Map a point on the dewarped image to the curved image
+    pub fn to_curved(&self, x: f32, y: f32) -> (f32, f32) {
+        (x, y + self.offset(x, y))
+    }
+}
+
+/// Least-squares quadratic through `points`
+fn fit_quadratic(points: &[(f32, f32)]) -> Option<[f64; 3]> {
+    // Normal equations in x shifted to the mean for numerical stability
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0 as f64).sum::<f64>() / n;
+    let mut sums = [0.0f64; 5];
+    let mut rhs = [0.0f64; 3];
+    for &(x, y) in points {
+        let x = x as f64 - mean_x;
+        let y = y as f64;
+        let mut power = 1.0;
+        for (i, sum) in sums.iter_mut().enumerate() {
+            *sum += power;
+            if i < 3 {
+                rhs[i] += power * y;
+            }
+            power *= x;
+        }
+    }
+    let m = [
+        [sums[0], sums[1], sums[2]],
+        [sums[1], sums[2], sums[3]],
+        [sums[2], sums[3], sums[4]],
+    ];
+    let det = |m: &[[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(&m);
+    if d.abs() < 1e-9 {
+        return None;
+    }
+
+    // Cramer's rule
+    let mut shifted = [0.0; 3];
+    for (column, coefficient) in shifted.iter_mut().enumerate() {
+        let mut replaced = m;
+        for row in 0..3 {
+            replaced[row][column] = rhs[row];
+        }
+        *coefficient = det(&replaced) / d;
+    }
+
+    // Expand c0 + c1 (x - m) + c2 (x - m)² back to powers of x
+    let [c0, c1, c2] = shifted;
+    Some([
+        c0 - c1 * mean_x + c2 * mean_x * mean_x,
+        c1 - 2.0 * c2 * mean_x,
+        c2,
+    ])
+}
+
+/// Union-find root of `i`, compressing the path on the way
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Chain character components into text lines
+///
+/// Each character is linked to its nearest right-hand neighbour at about
+/// the same height, and linked chains form lines.
+fn group_lines(components: &[Component], char_height: f32) -> Vec<Vec<Component>> {
+    let mut chars: Vec<Component> = components
+        .iter()
+        .copied()
+        .filter(|c| {
+            let height = c.height() as f32;
+            height >= char_height * 0.5 && height <= char_height * 3.0
+        })
+        .collect();
+    chars.sort_by_key(|c| c.left);
+
+    let center_y = |c: &Component| (c.top + c.bottom) as f32 / 2.0;
+    let max_gap = char_height * 1.5;
+
+    // Union-find over chained neighbours
+    let mut parent: Vec<usize> = (0..chars.len()).collect();
+
+    for i in 0..chars.len() {
+        let right = chars[i].right as f32;
+        let mut nearest: Option<(usize, f32)> = None;
+        for (j, candidate) in chars.iter().enumerate().skip(i + 1) {
+            let gap = candidate.left as f32 - right;
+            if gap > max_gap {
+                break;
+            }
+            if (center_y(candidate) - center_y(&chars[i])).abs() > char_height * 0.5 {
+                continue;
+            }
+            let gap = gap.max(0.0);
+            match nearest {
+                Some((_, best)) if best <= gap => {}
+                _ => nearest = Some((j, gap)),
+            }
+        }
+        if let Some((j, _)) = nearest {
+            let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+            parent[a] = b;
+        }
+    }
+
+    let mut groups: std::collections::HashMap<usize, Vec<Component>> = Default::default();
+    for (i, component) in chars.iter().enumerate() {
+        let r = root(&mut parent, i);
+        groups.entry(r).or_default().push(*component);
+    }
+    groups.into_values().collect()
+}
+
+/// Fit a curve to every sufficiently long text line
+fn fit_text_lines(img: &DynamicImage) -> Vec<FittedLine> {
+    let mask = text_mask(&img.to_luma8());
+    let (_, components) = find_components(&mask);
+    let Some(char_height) = estimate_character_height(&components, img.height()) else {
+        return Vec::new();
+    };
+
+    let min_span = img.width() as f32 * MIN_LINE_SPAN;
+    group_lines(&components, char_height)
+        .into_iter()
+        .filter(|line| line.len() >= MIN_LINE_COMPONENTS)
+        .filter_map(|line| {
+            let left = line.iter().map(|c| c.left).min()? as f32;
+            let right = line.iter().map(|c| c.right).max()? as f32;
+            if right - left < min_span {
+                return None;
+            }
+            let centers: Vec<(f32, f32)> = line
+                .iter()
+                .map(|c| {
+                    (
+                        (c.left + c.right) as f32 / 2.0,
+                        (c.top + c.bottom) as f32 / 2.0,
+                    )
+                })
+                .collect();
+            Some(FittedLine {
+                coefficients: fit_quadratic(&centers)?,
+                left,
+                right,
+            })
+        })
+        .collect()
+}
+
+fn mean_curvature(lines: &[FittedLine]) -> f32 {
+    lines.iter().map(FittedLine::sagitta).sum::<f32>() / lines.len().max(1) as f32
+}
+
+/// Straighten curved text lines
+///
+/// Steps:
+/// 1. Find character components and chain them into text lines
+/// 2. Fit a quadratic to the character centers of each long line
+/// 3. Interpolate the curves' vertical offsets between lines
+/// 4. Remap the image so every fitted line becomes horizontal
+///
+/// # Arguments
+/// * `img` - The input image, with text lines roughly horizontal
+///
+/// # Returns
+/// The dewarped image, the displacement that was applied and a report of
+/// the curvature before and after; the image is unchanged (and the report
+/// `None`) when too few lines were found or the page is already flat
+pub fn dewarp_page(
+    img: &DynamicImage,
+) -> (DynamicImage, Option<ImageTransform>, Option<DewarpReport>) {
+    let lines = fit_text_lines(img);
+    let curvature_before = mean_curvature(&lines);
+    if lines.len() < MIN_LINES || curvature_before < MIN_CURVATURE {
+        log::debug!(
+            "[Dewarp] {} line(s), curvature {:.1}px: no dewarping needed",
+            lines.len(),
+            curvature_before
+        );
+        return (img.clone(), None, None);
+    }
+
+    let line_count = lines.len();
+    let (width, height) = (img.width(), img.height());
+    let field = Arc::new(DisplacementField::new(lines, width, width as f32 / 2.0));
+
+    let rgba = img.to_rgba8();
+    let mut output = ImageBuffer::new(width, height);
+    warp_into_with(
+        &rgba,
+        |x, y| field.to_curved(x, y),
+        Interpolation::Bilinear,
+        Rgba([255, 255, 255, 255]),
+        &mut output,
+    );
+    let output = DynamicImage::ImageRgba8(output);
+
+    let remaining = fit_text_lines(&output);
+    let curvature_after = if remaining.is_empty() {
+        curvature_before
+    } else {
+        mean_curvature(&remaining)
+    };
+    let report = DewarpReport {
+        lines: line_count,
+        curvature_before,
+        curvature_after,
+        curvature_removed: (1.0 - curvature_after / curvature_before).clamp(0.0, 1.0),
+    };
+    log::debug!(
+        "[Dewarp] {} lines, curvature {:.1}px -> {:.1}px",
+        line_count,
+        curvature_before,
+        curvature_after
+    );
+
+    (output, Some(ImageTransform::Dewarp(field)), Some(report))
+}
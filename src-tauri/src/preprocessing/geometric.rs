@@ -3,9 +3,31 @@
 //! Includes skew correction and border removal
 
 use image::{DynamicImage, Rgba};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
+use super::transform::ImageTransform;
 use crate::binarization::calculate_otsu_threshold;
 
+/// Skew detection method
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SkewMethod {
+    /// Hough transform over Canny edges
+    Hough,
+    /// Projection-profile variance maximization
+    Projection,
+}
+
+/// Build the transform record for a `rotate_about_center` call on `img`
+fn rotation_transform(img: &DynamicImage, radians: f32) -> ImageTransform {
+    ImageTransform::Rotate {
+        radians,
+        center_x: img.width() as f32 / 2.0,
+        center_y: img.height() as f32 / 2.0,
+    }
+}
+
 /// Correct skew using Hough transform
 ///
 /// Based on Chinese-OCR3's approach for document deskewing.
@@ -20,8 +42,9 @@ use crate::binarization::calculate_otsu_threshold;
 /// * `img` - The input image
 ///
 /// # Returns
-/// A deskewed image or the original if no significant skew detected
-pub fn correct_skew(img: &DynamicImage) -> Result<DynamicImage, String> {
+/// A deskewed image (or the original if no significant skew detected)
+/// and the rotation that was applied, if any
+pub fn correct_skew(img: &DynamicImage) -> Result<(DynamicImage, Option<ImageTransform>), String> {
     use imageproc::edges::canny;
     use imageproc::hough::{detect_lines, LineDetectionOptions};
 
@@ -41,11 +64,11 @@ pub fn correct_skew(img: &DynamicImage) -> Result<DynamicImage, String> {
 
     // If no lines detected, return original image
     if lines.is_empty() {
-        println!("[Deskew] No lines detected, skipping correction");
-        return Ok(img.clone());
+        log::debug!("[Deskew] No lines detected, skipping correction");
+        return Ok((img.clone(), None));
     }
 
-    println!("[Deskew] Detected {} lines", lines.len());
+    log::debug!("[Deskew] Detected {} lines", lines.len());
 
     // Calculate angles from detected lines
     let mut angles: Vec<f32> = Vec::new();
@@ -69,20 +92,20 @@ pub fn correct_skew(img: &DynamicImage) -> Result<DynamicImage, String> {
     }
 
     if angles.is_empty() {
-        println!("[Deskew] No valid angles found after filtering");
-        return Ok(img.clone());
+        log::debug!("[Deskew] No valid angles found after filtering");
+        return Ok((img.clone(), None));
     }
 
     // Calculate average angle
     let sum: f32 = angles.iter().sum();
     let avg_angle = sum / angles.len() as f32;
 
-    println!("[Deskew] Average skew angle: {:.2}°", avg_angle);
+    log::debug!("[Deskew] Average skew angle: {:.2}°", avg_angle);
 
     // Only rotate if skew is significant (> 0.5 degrees)
     if avg_angle.abs() < 0.5 {
-        println!("[Deskew] Skew angle too small, skipping correction");
-        return Ok(img.clone());
+        log::debug!("[Deskew] Skew angle too small, skipping correction");
+        return Ok((img.clone(), None));
     }
 
     // Rotate image to correct skew
@@ -96,12 +119,13 @@ pub fn correct_skew(img: &DynamicImage) -> Result<DynamicImage, String> {
         Rgba([255u8, 255u8, 255u8, 255u8]),
     );
 
-    println!(
+    log::debug!(
         "[Deskew] Image rotated by {:.2}° to correct skew",
         -avg_angle
     );
 
-    Ok(DynamicImage::ImageRgba8(rotated))
+    let transform = rotation_transform(img, -avg_angle.to_radians());
+    Ok((DynamicImage::ImageRgba8(rotated), Some(transform)))
 }
 
 /// Correct skew using projection profile method
@@ -113,8 +137,11 @@ pub fn correct_skew(img: &DynamicImage) -> Result<DynamicImage, String> {
 /// * `img` - The input image
 ///
 /// # Returns
-/// A deskewed image or the original if no significant skew detected
-pub fn correct_skew_projection(img: &DynamicImage) -> Result<DynamicImage, String> {
+/// A deskewed image (or the original if no significant skew detected)
+/// and the rotation that was applied, if any
+pub fn correct_skew_projection(
+    img: &DynamicImage,
+) -> Result<(DynamicImage, Option<ImageTransform>), String> {
     use imageproc::contrast::{threshold, ThresholdType};
     use imageproc::geometric_transformations::{rotate_about_center, Interpolation};
 
@@ -167,15 +194,16 @@ pub fn correct_skew_projection(img: &DynamicImage) -> Result<DynamicImage, Strin
         }
     }
 
-    println!(
+    log::debug!(
         "[Deskew-Projection] Best angle: {:.2}° (variance: {:.0})",
-        best_angle, max_variance
+        best_angle,
+        max_variance
     );
 
     // Only rotate if skew is significant (> 0.3 degrees)
     if best_angle.abs() < 0.3 {
-        println!("[Deskew-Projection] Skew angle too small, skipping correction");
-        return Ok(img.clone());
+        log::debug!("[Deskew-Projection] Skew angle too small, skipping correction");
+        return Ok((img.clone(), None));
     }
 
     // Rotate original image
@@ -187,12 +215,13 @@ pub fn correct_skew_projection(img: &DynamicImage) -> Result<DynamicImage, Strin
         image::Rgba([255u8, 255u8, 255u8, 255u8]),
     );
 
-    println!(
+    log::debug!(
         "[Deskew-Projection] Image rotated by {:.2}° to correct skew",
         -best_angle
     );
 
-    Ok(DynamicImage::ImageRgba8(rotated))
+    let transform = rotation_transform(img, -best_angle.to_radians());
+    Ok((DynamicImage::ImageRgba8(rotated), Some(transform)))
 }
 
 /// Remove black borders using projection profile analysis
@@ -203,8 +232,8 @@ pub fn correct_skew_projection(img: &DynamicImage) -> Result<DynamicImage, Strin
 /// * `img` - The input image
 ///
 /// # Returns
-/// A cropped image with borders removed
-pub fn remove_borders(img: &DynamicImage) -> DynamicImage {
+/// A cropped image with borders removed and the crop that was applied, if any
+pub fn remove_borders(img: &DynamicImage) -> (DynamicImage, Option<ImageTransform>) {
     let gray = img.to_luma8();
     let (width, height) = gray.dimensions();
 
@@ -251,7 +280,7 @@ pub fn remove_borders(img: &DynamicImage) -> DynamicImage {
     let crop_width = crop_right - crop_left;
     let crop_height = crop_bottom - crop_top;
 
-    println!(
+    log::debug!(
         "[Border] Detected content area: {}x{} -> {}x{} (removed {:.1}%)",
         width,
         height,
@@ -262,9 +291,15 @@ pub fn remove_borders(img: &DynamicImage) -> DynamicImage {
 
     // Only crop if we're removing a significant border (>5%)
     if crop_width * crop_height > (width * height * 95 / 100) {
-        println!("[Border] Border too small, skipping removal");
-        return img.clone();
+        log::debug!("[Border] Border too small, skipping removal");
+        return (img.clone(), None);
     }
 
-    img.crop_imm(crop_left, crop_top, crop_width, crop_height)
+    (
+        img.crop_imm(crop_left, crop_top, crop_width, crop_height),
+        Some(ImageTransform::Crop {
+            left: crop_left,
+            top: crop_top,
+        }),
+    )
 }
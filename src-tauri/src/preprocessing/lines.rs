@@ -0,0 +1,317 @@
+//! Table and form line removal
+//!
+//! Grid lines in scanned forms and spreadsheet screenshots touch the
+//! characters inside their cells, and Tesseract merges the two. Long
+//! horizontal and vertical runs of dark pixels (a binary opening with
+//! line-shaped kernels) are taken as ruled lines and erased, except where
+//! a character stroke crosses them, and their extents are kept as a grid
+//! the recognized words can be assigned to.
+
+use image::{DynamicImage, GrayImage, Luma, Rgba};
+use serde::Serialize;
+
+use super::components::{estimate_character_height, find_components, text_mask, FOREGROUND};
+use super::transform::{BoundingBox, CoordinateMapper};
+
+/// Default minimum line length, in character heights
+const MIN_LINE_CHARACTER_HEIGHTS: f32 = 4.0;
+
+/// Smallest minimum line length, in pixels
+const MIN_LINE_LENGTH: u32 = 20;
+
+/// Longest gap bridged within a line (dashed or broken scan lines)
+const MAX_LINE_GAP: u32 = 2;
+
+/// Ruled lines found on a page
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableGrid {
+    /// Bounding boxes of the horizontal lines, top to bottom
+    pub horizontal: Vec<BoundingBox>,
+    /// Bounding boxes of the vertical lines, left to right
+    pub vertical: Vec<BoundingBox>,
+}
+
+impl TableGrid {
+    /// Map the grid from processed to source image coordinates
+    pub fn to_source(&self, mapper: &CoordinateMapper) -> TableGrid {
+        TableGrid {
+            horizontal: self.horizontal.iter().map(|b| mapper.map_box(*b)).collect(),
+            vertical: self.vertical.iter().map(|b| mapper.map_box(*b)).collect(),
+        }
+    }
+}
+
+/// Result of line removal
+#[derive(Debug, Clone)]
+pub struct LineRemoval {
+    /// Lines that were erased, in processed image coordinates
+    pub grid: TableGrid,
+    /// Minimum line length used, in pixels
+    pub min_length: u32,
+}
+
+/// Mask of the foreground pixels in runs of at least `min_length` along
+/// rows (`horizontal`) or columns, bridging gaps of up to
+/// [`MAX_LINE_GAP`] pixels
+fn long_runs(mask: &GrayImage, min_length: u32, horizontal: bool) -> GrayImage {
+    let (width, height) = mask.dimensions();
+    let (outer, inner) = if horizontal {
+        (height, width)
+    } else {
+        (width, height)
+    };
+    let at = |o: u32, i: u32| if horizontal { (i, o) } else { (o, i) };
+
+    let mut lines = GrayImage::new(width, height);
+    for o in 0..outer {
+        let mut start: Option<u32> = None;
+        let mut last = 0;
+        for i in 0..=inner {
+            let foreground = i < inner && {
+                let (x, y) = at(o, i);
+                mask.get_pixel(x, y).0[0] == FOREGROUND
+            };
+            if foreground {
+                start.get_or_insert(i);
+                last = i;
+                continue;
+            }
+            let Some(run_start) = start else {
+                continue;
+            };
+            if i < inner && i - last <= MAX_LINE_GAP {
+                continue;
+            }
+            if last + 1 - run_start >= min_length {
+                for j in run_start..=last {
+                    let (x, y) = at(o, j);
+                    if mask.get_pixel(x, y).0[0] == FOREGROUND {
+                        lines.put_pixel(x, y, Luma([FOREGROUND]));
+                    }
+                }
+            }
+            start = None;
+        }
+    }
+    lines
+}
+
+/// Bounding boxes of the line components at least `min_length` long
+fn line_boxes(lines: &GrayImage, min_length: u32, horizontal: bool) -> Vec<BoundingBox> {
+    let (_, components) = find_components(lines);
+    let mut boxes: Vec<BoundingBox> = components
+        .iter()
+        .filter(|c| {
+            let length = if horizontal { c.width() } else { c.height() };
+            length >= min_length
+        })
+        .map(|c| BoundingBox {
+            left: c.left,
+            top: c.top,
+            width: c.width(),
+            height: c.height(),
+        })
+        .collect();
+    if horizontal {
+        boxes.sort_by_key(|b| (b.top, b.left));
+    } else {
+        boxes.sort_by_key(|b| (b.left, b.top));
+    }
+    boxes
+}
+
+/// Detect ruled lines and erase them without cutting characters
+///
+/// Steps:
+/// 1. Threshold the image into a text mask
+/// 2. Keep horizontal and vertical runs at least `min_length` long
+/// 3. Erase line pixels (and their anti-aliased edges) to white, except
+///    where a character stroke continues on both sides of the line
+///
+/// # Arguments
+/// * `img` - The input image, dark lines on a light background
+/// * `min_length` - Shortest line in pixels; four character heights when
+///   `None`
+///
+/// # Returns
+/// The image with lines erased, and the lines found
+pub fn remove_lines(img: &DynamicImage, min_length: Option<u32>) -> (DynamicImage, LineRemoval) {
+    let mask = text_mask(&img.to_luma8());
+    let (width, height) = mask.dimensions();
+    let min_length = min_length.unwrap_or_else(|| {
+        let (_, components) = find_components(&mask);
+        match estimate_character_height(&components, height) {
+            Some(char_height) => (char_height * MIN_LINE_CHARACTER_HEIGHTS).round() as u32,
+            None => width.min(height) / 30,
+        }
+        .max(MIN_LINE_LENGTH)
+    });
+
+    let horizontal = long_runs(&mask, min_length, true);
+    let vertical = long_runs(&mask, min_length, false);
+    let is_line = |x: u32, y: u32| {
+        horizontal.get_pixel(x, y).0[0] == FOREGROUND || vertical.get_pixel(x, y).0[0] == FOREGROUND
+    };
+    // A foreground pixel that is not part of any line belongs to a character
+    let is_stroke = |x: i64, y: i64| {
+        x >= 0
+            && y >= 0
+            && x < width as i64
+            && y < height as i64
+            && mask.get_pixel(x as u32, y as u32).0[0] == FOREGROUND
+            && !is_line(x as u32, y as u32)
+    };
+
+    let mut rgba = img.to_rgba8();
+    let white = |rgba: &mut image::RgbaImage, x: i64, y: i64| {
+        if x >= 0 && y >= 0 && x < width as i64 && y < height as i64 {
+            let pixel = rgba.get_pixel_mut(x as u32, y as u32);
+            *pixel = Rgba([255, 255, 255, pixel.0[3]]);
+        }
+    };
+
+    for (lines, along_rows) in [(&horizontal, true), (&vertical, false)] {
+        for (x, y, pixel) in lines.enumerate_pixels() {
+            if pixel.0[0] != FOREGROUND {
+                continue;
+            }
+            // Extent of the line band across its direction at this pixel
+            let (step_x, step_y) = if along_rows { (0, 1) } else { (1, 0) };
+            let (x, y) = (x as i64, y as i64);
+            let in_band = |k: i64| {
+                let (bx, by) = (x + step_x * k, y + step_y * k);
+                bx >= 0
+                    && by >= 0
+                    && bx < width as i64
+                    && by < height as i64
+                    && lines.get_pixel(bx as u32, by as u32).0[0] == FOREGROUND
+            };
+            let mut before = -1;
+            while in_band(before) {
+                before -= 1;
+            }
+            let mut after = 1;
+            while in_band(after) {
+                after += 1;
+            }
+            let crossed = is_stroke(x + step_x * before, y + step_y * before)
+                && is_stroke(x + step_x * after, y + step_y * after);
+            if crossed {
+                continue;
+            }
+            white(&mut rgba, x, y);
+            for k in [before, after] {
+                if !is_stroke(x + step_x * k, y + step_y * k) {
+                    white(&mut rgba, x + step_x * k, y + step_y * k);
+                }
+            }
+        }
+    }
+
+    let grid = TableGrid {
+        horizontal: line_boxes(&horizontal, min_length, true),
+        vertical: line_boxes(&vertical, min_length, false),
+    };
+    log::debug!(
+        "[Lines] {} horizontal, {} vertical line(s), min length {}px",
+        grid.horizontal.len(),
+        grid.vertical.len(),
+        min_length
+    );
+
+    (
+        DynamicImage::ImageRgba8(rgba),
+        LineRemoval { grid, min_length },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(img: &mut GrayImage, xs: std::ops::Range<u32>, ys: std::ops::Range<u32>) {
+        for x in xs {
+            for y in ys.clone() {
+                img.put_pixel(x, y, Luma([0]));
+            }
+        }
+    }
+
+    fn is_dark(img: &DynamicImage, x: u32, y: u32) -> bool {
+        img.to_luma8().get_pixel(x, y).0[0] < 128
+    }
+
+    #[test]
+    fn ruled_grid_is_erased_and_reported() {
+        // A 2x2 table with 2px rules and a small glyph in each cell
+        let mut page = GrayImage::from_pixel(200, 160, Luma([255]));
+        for top in [20, 70, 120] {
+            fill(&mut page, 20..182, top..top + 2);
+        }
+        for left in [20, 100, 180] {
+            fill(&mut page, left..left + 2, 20..122);
+        }
+        for (left, top) in [(50, 40), (130, 40), (50, 90), (130, 90)] {
+            fill(&mut page, left..left + 6, top..top + 10);
+        }
+
+        let (cleaned, removal) = remove_lines(&DynamicImage::ImageLuma8(page), Some(40));
+
+        let tops: Vec<u32> = removal.grid.horizontal.iter().map(|b| b.top).collect();
+        let lefts: Vec<u32> = removal.grid.vertical.iter().map(|b| b.left).collect();
+        assert_eq!(tops, [20, 70, 120]);
+        assert_eq!(lefts, [20, 100, 180]);
+        assert!(removal
+            .grid
+            .horizontal
+            .iter()
+            .all(|b| b.width == 162 && b.height == 2));
+        assert!(removal
+            .grid
+            .vertical
+            .iter()
+            .all(|b| b.width == 2 && b.height == 102));
+
+        assert!(!is_dark(&cleaned, 60, 20));
+        assert!(!is_dark(&cleaned, 101, 50));
+        assert!(!is_dark(&cleaned, 180, 121));
+        assert!(is_dark(&cleaned, 52, 44));
+        assert!(is_dark(&cleaned, 133, 95));
+    }
+
+    #[test]
+    fn stroke_crossing_a_line_survives() {
+        // A character stem running through a rule, too short to be a line
+        let mut page = GrayImage::from_pixel(200, 100, Luma([255]));
+        fill(&mut page, 10..190, 50..52);
+        fill(&mut page, 100..103, 40..62);
+
+        let (cleaned, removal) = remove_lines(&DynamicImage::ImageLuma8(page), Some(40));
+
+        assert_eq!(removal.grid.horizontal.len(), 1);
+        assert!(removal.grid.vertical.is_empty());
+        assert!(!is_dark(&cleaned, 60, 50));
+        assert!(!is_dark(&cleaned, 150, 51));
+        for y in 40..62 {
+            assert!(is_dark(&cleaned, 101, y), "stem cut at y = {}", y);
+        }
+    }
+
+    #[test]
+    fn short_runs_are_not_lines() {
+        let mut mask = GrayImage::new(60, 10);
+        for x in (5..20).chain(22..30) {
+            mask.put_pixel(x, 3, Luma([FOREGROUND]));
+        }
+        for x in 40..55 {
+            mask.put_pixel(x, 6, Luma([FOREGROUND]));
+        }
+
+        // The gap of two pixels is bridged into one 25 px run
+        let lines = long_runs(&mask, 20, true);
+        assert!((5..30).all(|x| x == 20 || x == 21 || lines.get_pixel(x, 3).0[0] == FOREGROUND));
+        assert!((40..55).all(|x| lines.get_pixel(x, 6).0[0] == 0));
+        assert!(long_runs(&mask, 20, false).pixels().all(|p| p.0[0] == 0));
+    }
+}
@@ -5,13 +5,49 @@
 //! - Brightness and contrast adjustment
 //! - Sharpening
 //! - Noise reduction (Gaussian blur, bilateral filter)
+//! - Perspective correction for photographed pages
 //! - Border removal
+//! - Orientation detection (90°/180°/270° rotation)
 //! - Skew correction (deskewing)
+//! - Page curl dewarping
+//! - Scale normalization to a target text height
+//! - Dark-mode (inverted text) detection and inversion
+//! - Color dropout (stamps, highlighter, colored form lines)
+//! - Shadow removal (background flattening)
+//! - Table and form line removal
+//! - Connected-component despeckling
+//! - Coordinate mapping back to the source image
 
 mod adjustments;
+mod background;
+mod color;
+mod components;
+mod despeckle;
+mod dewarp;
 mod filters;
 mod geometric;
+mod lines;
+mod orientation;
+mod perspective;
+mod polarity;
+mod scale;
+mod transform;
 
 pub use adjustments::{adjust_brightness, adjust_contrast, adjust_sharpness};
+pub use background::{
+    measure_illumination_unevenness, normalize_background, BackgroundMethod, FlattenMode,
+    UNEVEN_ILLUMINATION_THRESHOLD,
+};
+pub use color::{drop_colors, DropoutMode, HueSpec};
+pub use despeckle::{despeckle, DespeckleOptions, DEFAULT_SPECK_SIZE};
+pub use dewarp::{dewarp_page, DewarpReport};
 pub use filters::{apply_gaussian_blur, apply_bilateral_filter};
-pub use geometric::{correct_skew, correct_skew_projection, remove_borders};
+pub use geometric::{correct_skew, correct_skew_projection, remove_borders, SkewMethod};
+pub use lines::{remove_lines, TableGrid};
+pub use orientation::{detect_orientation_projection, rotate_orthogonal};
+pub use perspective::{correct_perspective, detect_document_corners};
+pub use polarity::{detect_polarity, normalize_polarity, Polarity, PolarityDetection};
+pub use scale::{
+    estimate_text_height, normalization_factor, rescale, ResampleFilter, DEFAULT_TARGET_TEXT_HEIGHT,
+};
+pub use transform::{BoundingBox, CoordinateMapper};
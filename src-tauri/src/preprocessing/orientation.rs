@@ -0,0 +1,315 @@
+//! Page orientation detection and correction
+//!
+//! Detects pages rotated by a multiple of 90° (sideways or upside-down
+//! photos) so they can be turned upright before deskewing, which only
+//! handles small angles.
+
+use image::{imageops, DynamicImage, GrayImage};
+
+use super::transform::ImageTransform;
+use crate::binarization::calculate_otsu_threshold;
+
+/// Longest side of the downscaled analysis image
+const ANALYSIS_SIZE: u32 = 1000;
+
+/// Minimum text-line height in analysis pixels
+const MIN_LINE_HEIGHT: usize = 4;
+
+/// Share of a line's densest row that rows of the x-height band reach
+const X_HEIGHT_SHARE: f64 = 0.5;
+
+/// How many times higher one axis's profile variance must be to decide
+/// the line direction; sparse or single-line images score close on both
+const MIN_AXIS_VARIANCE_RATIO: f64 = 1.5;
+
+/// Orientation estimated from projection profiles
+#[derive(Debug, Clone, Copy)]
+pub struct ProjectionOrientation {
+    /// Clockwise rotation (0, 90, 180 or 270) needed to make text upright
+    pub rotation_degrees: u32,
+    /// Strength of the evidence for both the line direction and the
+    /// up/down decision, 0.0-1.0
+    pub confidence: f32,
+}
+
+/// Detect page orientation using projection profiles
+///
+/// Steps:
+/// 1. Binarize a downscaled copy with Otsu (dark text assumed)
+/// 2. Compare the variance of row and column ink profiles: text lines
+///    make the profile across them strongly periodic, so the axis with
+///    clearly higher variance (at least [`MIN_AXIS_VARIANCE_RATIO`] times
+///    the other) is perpendicular to the lines
+/// 3. Within each text line, find the x-height band (the rows holding
+///    most of the ink) and compare the ink above it with the ink below:
+///    ascenders and capitals outnumber descenders in Latin text, so
+///    upright lines carry more ink above the band
+///
+/// # Arguments
+/// * `img` - The input image
+///
+/// # Returns
+/// The rotation to apply and a confidence combining the line direction
+/// and up/down evidence; no rotation with zero confidence when the line
+/// direction is unclear
+pub fn detect_orientation_projection(img: &DynamicImage) -> ProjectionOrientation {
+    let gray = img
+        .resize(ANALYSIS_SIZE, ANALYSIS_SIZE, imageops::FilterType::Triangle)
+        .to_luma8();
+    let threshold = calculate_otsu_threshold(&gray);
+    let ink = GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        image::Luma([(gray.get_pixel(x, y)[0] < threshold) as u8])
+    });
+
+    let (rows, cols) = ink_profiles(&ink);
+    let (row_variance, col_variance) = (profile_variance(&rows), profile_variance(&cols));
+    let (low, high) = if col_variance > row_variance {
+        (row_variance, col_variance)
+    } else {
+        (col_variance, row_variance)
+    };
+    if high == 0.0 || high < low * MIN_AXIS_VARIANCE_RATIO {
+        log::debug!(
+            "[Orientation] Line direction unclear (row variance {:.3}, column variance {:.3})",
+            row_variance,
+            col_variance
+        );
+        return ProjectionOrientation {
+            rotation_degrees: 0,
+            confidence: 0.0,
+        };
+    }
+    let sideways = col_variance > row_variance;
+    // 0 at the minimum ratio, approaching 1 as the other axis flattens out
+    let axis_confidence = (1.0 - MIN_AXIS_VARIANCE_RATIO * low / high) as f32;
+
+    // Turn sideways text horizontal before the up/down test
+    let horizontal = if sideways {
+        imageops::rotate90(&ink)
+    } else {
+        ink
+    };
+    let (upright, upright_confidence) = upright_votes(&horizontal);
+    // The weaker of the two decisions bounds the result
+    let confidence = axis_confidence.min(upright_confidence);
+
+    let rotation_degrees = match (sideways, upright) {
+        (false, true) => 0,
+        (false, false) => 180,
+        (true, true) => 90,
+        (true, false) => 270,
+    };
+
+    ProjectionOrientation {
+        rotation_degrees,
+        confidence,
+    }
+}
+
+/// Ink pixel counts per row and per column
+fn ink_profiles(ink: &GrayImage) -> (Vec<f64>, Vec<f64>) {
+    let (width, height) = ink.dimensions();
+    let mut rows = vec![0.0; height as usize];
+    let mut cols = vec![0.0; width as usize];
+    for (x, y, pixel) in ink.enumerate_pixels() {
+        if pixel[0] != 0 {
+            rows[y as usize] += 1.0;
+            cols[x as usize] += 1.0;
+        }
+    }
+    (rows, cols)
+}
+
+/// Variance of a profile normalized by its squared mean, so profiles of
+/// different lengths are comparable
+fn profile_variance(profile: &[f64]) -> f64 {
+    if profile.is_empty() {
+        return 0.0;
+    }
+    let mean = profile.iter().sum::<f64>() / profile.len() as f64;
+    if mean == 0.0 {
+        return 0.0;
+    }
+    let variance = profile.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / profile.len() as f64;
+    variance / (mean * mean)
+}
+
+/// Vote on whether horizontal text lines are upright
+///
+/// # Returns
+/// Whether the majority of ink-weighted lines have more ink above their
+/// x-height band than below it, and the fraction by which the majority
+/// wins (0.0-1.0)
+fn upright_votes(ink: &GrayImage) -> (bool, f32) {
+    let (rows, _) = ink_profiles(ink);
+    let max = rows.iter().cloned().fold(0.0, f64::max);
+    if max == 0.0 {
+        return (true, 0.0);
+    }
+
+    // Rows with a meaningful amount of ink belong to a text line
+    let line_threshold = max * 0.05;
+    let mut top_heavy = 0.0;
+    let mut bottom_heavy = 0.0;
+    let mut y = 0;
+    while y < rows.len() {
+        if rows[y] <= line_threshold {
+            y += 1;
+            continue;
+        }
+        let start = y;
+        while y < rows.len() && rows[y] > line_threshold {
+            y += 1;
+        }
+        if y - start < MIN_LINE_HEIGHT {
+            continue;
+        }
+
+        let line = &rows[start..y];
+        let mass: f64 = line.iter().sum();
+        // The x-height band is where every letter has ink
+        let peak = line.iter().cloned().fold(0.0, f64::max);
+        let band_start = line.iter().position(|&v| v >= peak * X_HEIGHT_SHARE);
+        let band_end = line.iter().rposition(|&v| v >= peak * X_HEIGHT_SHARE);
+        let (Some(band_start), Some(band_end)) = (band_start, band_end) else {
+            continue;
+        };
+        let above: f64 = line[..band_start].iter().sum();
+        let below: f64 = line[band_end + 1..].iter().sum();
+        // Weight each line by its ink so specks barely count
+        if above > below {
+            top_heavy += mass;
+        } else if below > above {
+            bottom_heavy += mass;
+        }
+    }
+
+    let total = top_heavy + bottom_heavy;
+    if total == 0.0 {
+        return (true, 0.0);
+    }
+    (
+        top_heavy >= bottom_heavy,
+        ((top_heavy - bottom_heavy).abs() / total) as f32,
+    )
+}
+
+/// Rotate an image clockwise by a multiple of 90°
+///
+/// # Arguments
+/// * `img` - The input image
+/// * `degrees` - Clockwise rotation; must be 0, 90, 180 or 270
+///
+/// # Returns
+/// The rotated image and the rotation that was applied, if any
+pub fn rotate_orthogonal(
+    img: &DynamicImage,
+    degrees: u32,
+) -> Result<(DynamicImage, Option<ImageTransform>), String> {
+    let rotated = match degrees {
+        0 => return Ok((img.clone(), None)),
+        90 => img.rotate90(),
+        180 => img.rotate180(),
+        270 => img.rotate270(),
+        _ => return Err(format!("Unsupported rotation: {}°", degrees)),
+    };
+
+    let transform = ImageTransform::QuarterTurn {
+        turns: degrees / 90,
+        width: img.width(),
+        height: img.height(),
+    };
+    Ok((rotated, Some(transform)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    /// A page of dark "text" on white: lines of letters with an x-height
+    /// body, where every third letter has an ascender and every seventh a
+    /// shorter descender, as in Latin text
+    fn text_page() -> DynamicImage {
+        let mut page = GrayImage::from_pixel(600, 800, Luma([255]));
+        for line in 0..20 {
+            let top = 40 + line * 36;
+            for letter in 0..45 {
+                let left = 30 + letter * 12 + (letter / 5) * 6;
+                if left + 8 >= 570 {
+                    break;
+                }
+                for x in left..left + 8 {
+                    for y in top + 8..top + 20 {
+                        page.put_pixel(x, y, Luma([0]));
+                    }
+                }
+                let stroke = left..left + 2;
+                if letter % 3 == 0 {
+                    for x in stroke.clone() {
+                        for y in top..top + 8 {
+                            page.put_pixel(x, y, Luma([0]));
+                        }
+                    }
+                }
+                if letter % 7 == 0 {
+                    for x in stroke {
+                        for y in top + 20..top + 24 {
+                            page.put_pixel(x, y, Luma([0]));
+                        }
+                    }
+                }
+            }
+        }
+        DynamicImage::ImageLuma8(page)
+    }
+
+    #[test]
+    fn upright_page_is_left_alone() {
+        let detection = detect_orientation_projection(&text_page());
+        assert_eq!(detection.rotation_degrees, 0);
+        assert!(detection.confidence > 0.2, "{}", detection.confidence);
+    }
+
+    #[test]
+    fn turned_pages_are_rotated_back() {
+        let page = text_page();
+        for (turned, correction) in [
+            (page.rotate90(), 270),
+            (page.rotate180(), 180),
+            (page.rotate270(), 90),
+        ] {
+            let detection = detect_orientation_projection(&turned);
+            assert_eq!(detection.rotation_degrees, correction);
+            assert!(detection.confidence > 0.2, "{}", detection.confidence);
+
+            let (restored, transform) = rotate_orthogonal(&turned, correction).unwrap();
+            assert!(transform.is_some());
+            assert_eq!(restored.to_luma8(), page.to_luma8());
+        }
+    }
+
+    #[test]
+    fn single_line_is_not_turned_sideways() {
+        // One short line has no periodic profile on either axis
+        let mut strip = GrayImage::from_pixel(200, 60, Luma([255]));
+        for x in 20..180 {
+            for y in 25..37 {
+                if x % 12 < 8 {
+                    strip.put_pixel(x, y, Luma([0]));
+                }
+            }
+        }
+        let detection = detect_orientation_projection(&DynamicImage::ImageLuma8(strip));
+        assert!(detection.rotation_degrees == 0 || detection.rotation_degrees == 180);
+    }
+
+    #[test]
+    fn only_quarter_turns_are_supported() {
+        let page = DynamicImage::new_luma8(4, 2);
+        assert!(rotate_orthogonal(&page, 45).is_err());
+        let (same, transform) = rotate_orthogonal(&page, 0).unwrap();
+        assert!(transform.is_none());
+        assert_eq!((same.width(), same.height()), (4, 2));
+    }
+}
@@ -0,0 +1,220 @@
+//! Perspective correction for photographed documents
+//!
+//! Finds the page outline as the largest convex quadrilateral among the
+//! edge contours and warps it to an upright rectangle, so a phone photo of
+//! a sheet of paper becomes a flat scan.
+
+use image::{DynamicImage, ImageBuffer, Rgba};
+use imageproc::geometric_transformations::{warp_into, Interpolation, Projection};
+use imageproc::point::Point;
+
+use super::transform::ImageTransform;
+
+/// Longest side of the downscaled copy used for outline detection
+const DETECTION_SIZE: u32 = 1000;
+
+/// Smallest page outline, as a share of the image area
+const MIN_QUAD_AREA: f64 = 0.2;
+
+/// Outlines whose corners are all this close to the image corners (as a
+/// share of the image size) need no correction
+const FULL_FRAME_MARGIN: f32 = 0.03;
+
+/// Polygon approximation tolerance, as a share of the outline perimeter
+const APPROXIMATION_EPSILON: f64 = 0.02;
+
+/// Smallest rectified page side, in pixels
+const MIN_OUTPUT_SIDE: f32 = 16.0;
+
+/// Top-left, top-right, bottom-right and bottom-left extremes of a point
+/// set, found along the image diagonals
+fn extreme_corners(points: &[(f32, f32)]) -> [(f32, f32); 4] {
+    let extreme = |key: fn(&(f32, f32)) -> f32, max: bool| {
+        let iter = points.iter().copied();
+        if max {
+            iter.max_by(|a, b| key(a).total_cmp(&key(b)))
+        } else {
+            iter.min_by(|a, b| key(a).total_cmp(&key(b)))
+        }
+        .unwrap_or_default()
+    };
+    [
+        extreme(|p| p.0 + p.1, false),
+        extreme(|p| p.0 - p.1, true),
+        extreme(|p| p.0 + p.1, true),
+        extreme(|p| p.0 - p.1, false),
+    ]
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+/// Area of a polygon given in order
+fn polygon_area(points: &[(f32, f32)]) -> f64 {
+    let mut area = 0.0;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area += (a.0 as f64) * (b.1 as f64) - (b.0 as f64) * (a.1 as f64);
+    }
+    area.abs() / 2.0
+}
+
+/// Four corners approximating a convex hull
+///
+/// Uses the Douglas-Peucker approximation when it yields a quadrilateral,
+/// otherwise the hull's extreme points along the diagonals.
+fn hull_quad(hull: &[Point<i32>]) -> [(f32, f32); 4] {
+    let perimeter = imageproc::geometry::arc_length(hull, true);
+    let approx =
+        imageproc::geometry::approximate_polygon_dp(hull, APPROXIMATION_EPSILON * perimeter, true);
+    let points: Vec<(f32, f32)> = if approx.len() == 4 {
+        approx
+    } else {
+        hull.to_vec()
+    }
+    .iter()
+    .map(|p| (p.x as f32, p.y as f32))
+    .collect();
+    extreme_corners(&points)
+}
+
+/// Detect the outline of a document page in a photo
+///
+/// Steps:
+/// 1. Downscale and blur a grayscale copy
+/// 2. Apply Canny edge detection and close small gaps by dilation
+/// 3. Take the convex hull of each outer contour
+/// 4. Keep the largest hull covering at least 20% of the image, reduced to
+///    four corners
+///
+/// # Arguments
+/// * `img` - The input image
+///
+/// # Returns
+/// The page corners (top-left, top-right, bottom-right, bottom-left) in
+/// image coordinates, or `None` if no outline was found or the page
+/// already fills the frame
+pub fn detect_document_corners(img: &DynamicImage) -> Option<[(f32, f32); 4]> {
+    use imageproc::contours::{find_contours, BorderType};
+    use imageproc::distance_transform::Norm;
+    use imageproc::edges::canny;
+    use imageproc::filter::gaussian_blur_f32;
+    use imageproc::morphology::dilate;
+
+    let (width, height) = (img.width(), img.height());
+    let small = if width.max(height) > DETECTION_SIZE {
+        img.thumbnail(DETECTION_SIZE, DETECTION_SIZE)
+    } else {
+        img.clone()
+    };
+    let ratio = width as f32 / small.width() as f32;
+
+    let gray = gaussian_blur_f32(&small.to_luma8(), 2.0);
+    let edges = dilate(&canny(&gray, 30.0, 90.0), Norm::LInf, 2);
+
+    let min_area = MIN_QUAD_AREA * (small.width() as f64 * small.height() as f64);
+    let mut best: Option<([(f32, f32); 4], f64)> = None;
+    for contour in find_contours::<i32>(&edges) {
+        if contour.border_type != BorderType::Outer || contour.points.len() < 4 {
+            continue;
+        }
+        let hull = imageproc::geometry::convex_hull(contour.points);
+        if imageproc::geometry::contour_area(&hull) < min_area {
+            continue;
+        }
+        let quad = hull_quad(&hull);
+        let area = polygon_area(&quad);
+        match best {
+            Some((_, best_area)) if best_area >= area => {}
+            _ if area >= min_area => best = Some((quad, area)),
+            _ => {}
+        }
+    }
+
+    let (quad, _) = best?;
+    let corners = quad.map(|(x, y)| (x * ratio, y * ratio));
+
+    let frame = [
+        (0.0, 0.0),
+        (width as f32, 0.0),
+        (width as f32, height as f32),
+        (0.0, height as f32),
+    ];
+    let margin = FULL_FRAME_MARGIN * width.max(height) as f32;
+    if corners
+        .iter()
+        .zip(frame)
+        .all(|(&corner, frame_corner)| distance(corner, frame_corner) < margin)
+    {
+        log::debug!("[Perspective] Page fills the frame, no correction needed");
+        return None;
+    }
+
+    log::debug!("[Perspective] Detected page corners: {:?}", corners);
+    Some(corners)
+}
+
+/// Warp a quadrilateral region to an upright rectangle
+///
+/// The output size is the longer of each pair of opposite sides, so the
+/// page keeps roughly its original resolution.
+///
+/// # Arguments
+/// * `img` - The input image
+/// * `corners` - The page corners: top-left, top-right, bottom-right,
+///   bottom-left
+///
+/// # Returns
+/// The rectified page and the perspective mapping that was applied
+pub fn correct_perspective(
+    img: &DynamicImage,
+    corners: [(f32, f32); 4],
+) -> Result<(DynamicImage, Option<ImageTransform>), String> {
+    let [top_left, top_right, bottom_right, bottom_left] = corners;
+    let out_width = distance(top_left, top_right).max(distance(bottom_left, bottom_right));
+    let out_height = distance(top_left, bottom_left).max(distance(top_right, bottom_right));
+    if out_width < MIN_OUTPUT_SIDE || out_height < MIN_OUTPUT_SIDE {
+        return Err(format!(
+            "Document corners enclose too small an area ({:.0}x{:.0})",
+            out_width, out_height
+        ));
+    }
+    let (out_width, out_height) = (out_width.round(), out_height.round());
+
+    let projection = Projection::from_control_points(
+        [top_left, top_right, bottom_right, bottom_left],
+        [
+            (0.0, 0.0),
+            (out_width, 0.0),
+            (out_width, out_height),
+            (0.0, out_height),
+        ],
+    )
+    .ok_or_else(|| "Document corners do not form a valid quadrilateral".to_string())?;
+
+    let rgba = img.to_rgba8();
+    let mut output = ImageBuffer::new(out_width as u32, out_height as u32);
+    warp_into(
+        &rgba,
+        &projection,
+        Interpolation::Bicubic,
+        Rgba([255, 255, 255, 255]),
+        &mut output,
+    );
+
+    log::debug!(
+        "[Perspective] Rectified {}x{} -> {}x{}",
+        img.width(),
+        img.height(),
+        out_width,
+        out_height
+    );
+
+    Ok((
+        DynamicImage::ImageRgba8(output),
+        Some(ImageTransform::Perspective {
+            to_source: projection.invert(),
+        }),
+    ))
+}
@@ -0,0 +1,471 @@
+//! Text polarity detection and normalization
+//!
+//! Dark-mode screenshots put light text on dark backgrounds, which
+//! thresholding turns into white text on black. Dark pixels of similar
+//! luminance are grouped into connected regions, and light components
+//! enclosed by a single region are its text. A large region counts as inverted when its light
+//! text outweighs any darker strokes on it, so UIs mixing light and dark
+//! panels are inverted panel by panel, whatever their shape, while shadows
+//! over dark text (dark regions without light text) are left alone.
+
+use image::{DynamicImage, GrayImage, Luma, Rgba};
+use imageproc::region_labelling::{connected_components, Connectivity};
+use serde::Serialize;
+
+use super::components::{LabelImage, FOREGROUND};
+
+/// Pixels darker than this are dark background or dark text
+const DARK_BACKGROUND_LEVEL: u8 = 128;
+
+/// Largest luminance step between neighbouring pixels of one dark region
+const REGION_STEP: u8 = 24;
+
+/// Smallest width and height of a dark region that can hold text
+const MIN_PANEL_SIZE: u32 = 48;
+
+/// Fewest enclosed light components (glyphs) in an inverted region
+const MIN_GLYPHS: usize = 3;
+
+/// Pixels at least this far below a region's median luminance are dark
+/// strokes on it
+const STROKE_CONTRAST: usize = 48;
+
+/// Share of a region that its light text must cover
+const MIN_STROKE_SHARE: f32 = 0.002;
+
+/// Share of the image in inverted regions below which it counts as normal,
+/// and above which (from the top) it counts as inverted; in between it is
+/// mixed
+const MIXED_MARGIN: f32 = 0.1;
+
+/// Text polarity of an image
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Polarity {
+    /// Dark text on a light background
+    Normal,
+    /// Light text on a dark background
+    Inverted,
+    /// Both light and dark regions
+    Mixed,
+}
+
+/// Result of polarity detection
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolarityDetection {
+    pub polarity: Polarity,
+    /// Share of the image in dark regions holding light text (0.0-1.0)
+    pub dark_fraction: f32,
+}
+
+/// The dark pixels around a light component
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Enclosure {
+    /// No dark neighbours seen yet
+    None,
+    /// Every dark neighbour belongs to this dark region
+    Region(u32),
+    /// Touches several dark regions
+    Several,
+}
+
+/// Extent, size and enclosed text of a dark region
+struct Region {
+    left: u32,
+    top: u32,
+    right: u32,
+    bottom: u32,
+    area: u32,
+    /// Luminance histogram, kept only for regions large enough to be panels
+    histogram: Option<Box<[u32; 256]>>,
+    glyphs: usize,
+    glyph_area: u32,
+}
+
+impl Region {
+    fn is_panel_sized(&self) -> bool {
+        self.right - self.left + 1 >= MIN_PANEL_SIZE && self.bottom - self.top + 1 >= MIN_PANEL_SIZE
+    }
+
+    /// Whether the region is a dark background with light text on it
+    fn is_inverted(&self) -> bool {
+        let Some(histogram) = &self.histogram else {
+            return false;
+        };
+        // Text covers less of a panel than its background does, but enough
+        // to be more than a few glints
+        let total = self.area + self.glyph_area;
+        if self.glyphs < MIN_GLYPHS
+            || self.glyph_area >= self.area
+            || (self.glyph_area as f32) < total as f32 * MIN_STROKE_SHARE
+        {
+            return false;
+        }
+
+        // Dark text on a dark-ish region (a shadow) outweighs its glints
+        let mut count = 0;
+        let median = histogram
+            .iter()
+            .position(|&bin| {
+                count += bin;
+                count > self.area / 2
+            })
+            .unwrap_or(0);
+        let dark_strokes: u32 = histogram[..median.saturating_sub(STROKE_CONTRAST)]
+            .iter()
+            .sum();
+        self.glyph_area > dark_strokes
+    }
+}
+
+/// Dark regions and the light components they enclose, with the inverted
+/// ones marked
+struct RegionMap {
+    dark_labels: LabelImage,
+    light_labels: LabelImage,
+    /// Per dark label - 1
+    dark_inverted: Vec<bool>,
+    /// Per light label - 1
+    light_inverted: Vec<bool>,
+    inverted_area: u64,
+}
+
+impl RegionMap {
+    fn is_inverted(&self, x: u32, y: u32) -> bool {
+        let dark = self.dark_labels.get_pixel(x, y).0[0] as usize;
+        if dark > 0 {
+            return self.dark_inverted[dark - 1];
+        }
+        let light = self.light_labels.get_pixel(x, y).0[0] as usize;
+        light > 0 && self.light_inverted[light - 1]
+    }
+
+    fn detection(&self) -> PolarityDetection {
+        let (width, height) = self.dark_labels.dimensions();
+        let pixels = (width as u64 * height as u64).max(1);
+        let dark_fraction = self.inverted_area as f32 / pixels as f32;
+        let polarity = if dark_fraction < MIXED_MARGIN {
+            Polarity::Normal
+        } else if dark_fraction > 1.0 - MIXED_MARGIN {
+            Polarity::Inverted
+        } else {
+            Polarity::Mixed
+        };
+        PolarityDetection {
+            polarity,
+            dark_fraction,
+        }
+    }
+}
+
+/// Label dark regions, growing each across 8-connected dark neighbours
+/// that differ by at most [`REGION_STEP`], so a dark panel and a shadow
+/// beside it stay apart while gradients within a panel hold together
+fn label_dark_regions(gray: &GrayImage) -> LabelImage {
+    let (width, height) = gray.dimensions();
+    let mut labels = LabelImage::new(width, height);
+    let mut next_label = 0;
+    let mut stack = Vec::new();
+    for (x, y, pixel) in gray.enumerate_pixels() {
+        if pixel.0[0] >= DARK_BACKGROUND_LEVEL || labels.get_pixel(x, y).0[0] != 0 {
+            continue;
+        }
+        next_label += 1;
+        labels.put_pixel(x, y, Luma([next_label]));
+        stack.push((x, y));
+        while let Some((cx, cy)) = stack.pop() {
+            let level = gray.get_pixel(cx, cy).0[0];
+            for ny in cy.saturating_sub(1)..=(cy + 1).min(height - 1) {
+                for nx in cx.saturating_sub(1)..=(cx + 1).min(width - 1) {
+                    let neighbour = gray.get_pixel(nx, ny).0[0];
+                    if neighbour < DARK_BACKGROUND_LEVEL
+                        && neighbour.abs_diff(level) <= REGION_STEP
+                        && labels.get_pixel(nx, ny).0[0] == 0
+                    {
+                        labels.put_pixel(nx, ny, Luma([next_label]));
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+        }
+    }
+    labels
+}
+
+/// Find the dark regions holding light text
+///
+/// Light pixels are grouped 4-connected, so a glyph never joins the light
+/// area outside its panel through a diagonal gap.
+fn classify_regions(gray: &GrayImage) -> RegionMap {
+    let (width, height) = gray.dimensions();
+    let mut mask = GrayImage::new(width, height);
+    for (x, y, pixel) in gray.enumerate_pixels() {
+        if pixel.0[0] < DARK_BACKGROUND_LEVEL {
+            mask.put_pixel(x, y, Luma([FOREGROUND]));
+        }
+    }
+    let dark_labels = label_dark_regions(gray);
+    let light_labels = connected_components(&mask, Connectivity::Four, Luma([FOREGROUND]));
+
+    let mut regions: Vec<Region> = Vec::new();
+    for (x, y, label) in dark_labels.enumerate_pixels() {
+        let label = label.0[0] as usize;
+        if label == 0 {
+            continue;
+        }
+        if regions.len() < label {
+            regions.resize_with(label, || Region {
+                left: u32::MAX,
+                top: u32::MAX,
+                right: 0,
+                bottom: 0,
+                area: 0,
+                histogram: None,
+                glyphs: 0,
+                glyph_area: 0,
+            });
+        }
+        let region = &mut regions[label - 1];
+        region.left = region.left.min(x);
+        region.top = region.top.min(y);
+        region.right = region.right.max(x);
+        region.bottom = region.bottom.max(y);
+        region.area += 1;
+    }
+
+    for region in regions.iter_mut().filter(|r| r.is_panel_sized()) {
+        region.histogram = Some(Box::new([0; 256]));
+    }
+    for (x, y, label) in dark_labels.enumerate_pixels() {
+        let label = label.0[0] as usize;
+        if label == 0 {
+            continue;
+        }
+        if let Some(histogram) = &mut regions[label - 1].histogram {
+            histogram[gray.get_pixel(x, y).0[0] as usize] += 1;
+        }
+    }
+
+    // A light component is text of the one dark region around it
+    let light_count = light_labels.pixels().map(|p| p.0[0]).max().unwrap_or(0) as usize;
+    let mut enclosures = vec![Enclosure::None; light_count];
+    let mut light_areas = vec![0u32; light_count];
+    for (x, y, label) in light_labels.enumerate_pixels() {
+        let label = label.0[0] as usize;
+        if label == 0 {
+            continue;
+        }
+        light_areas[label - 1] += 1;
+        let neighbours = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for (nx, ny) in neighbours {
+            if nx >= width || ny >= height {
+                continue;
+            }
+            let dark = dark_labels.get_pixel(nx, ny).0[0];
+            if dark == 0 {
+                continue;
+            }
+            let enclosure = &mut enclosures[label - 1];
+            *enclosure = match *enclosure {
+                Enclosure::None => Enclosure::Region(dark),
+                Enclosure::Region(region) if region == dark => Enclosure::Region(region),
+                _ => Enclosure::Several,
+            };
+        }
+    }
+    for (enclosure, &area) in enclosures.iter().zip(&light_areas) {
+        if let Enclosure::Region(dark) = enclosure {
+            let region = &mut regions[*dark as usize - 1];
+            region.glyphs += 1;
+            region.glyph_area += area;
+        }
+    }
+
+    let dark_inverted: Vec<bool> = regions.iter().map(Region::is_inverted).collect();
+    let light_inverted = enclosures
+        .iter()
+        .map(|enclosure| match enclosure {
+            Enclosure::Region(dark) => dark_inverted[*dark as usize - 1],
+            _ => false,
+        })
+        .collect();
+    let inverted_area = regions
+        .iter()
+        .zip(&dark_inverted)
+        .filter(|(_, &inverted)| inverted)
+        .map(|(region, _)| (region.area + region.glyph_area) as u64)
+        .sum();
+
+    RegionMap {
+        dark_labels,
+        light_labels,
+        dark_inverted,
+        light_inverted,
+        inverted_area,
+    }
+}
+
+/// Detect whether an image has light text on a dark background
+///
+/// # Arguments
+/// * `img` - The input image
+///
+/// # Returns
+/// The overall polarity and the share of the image in dark regions holding
+/// light text
+pub fn detect_polarity(img: &DynamicImage) -> PolarityDetection {
+    classify_regions(&img.to_luma8()).detection()
+}
+
+/// Invert dark-background regions so all text is dark on light
+///
+/// Fully inverted images are inverted as a whole; mixed images are
+/// inverted region by region, each dark panel together with its text.
+///
+/// # Arguments
+/// * `img` - The input image
+///
+/// # Returns
+/// The normalized image (unchanged when already dark on light) and the
+/// detected polarity
+pub fn normalize_polarity(img: &DynamicImage) -> (DynamicImage, PolarityDetection) {
+    let regions = classify_regions(&img.to_luma8());
+    let detection = regions.detection();
+
+    match detection.polarity {
+        Polarity::Normal => (img.clone(), detection),
+        Polarity::Inverted => {
+            let mut inverted = img.clone();
+            inverted.invert();
+            (inverted, detection)
+        }
+        Polarity::Mixed => {
+            let mut rgba = img.to_rgba8();
+            for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+                if regions.is_inverted(x, y) {
+                    let [r, g, b, a] = pixel.0;
+                    *pixel = Rgba([255 - r, 255 - g, 255 - b, a]);
+                }
+            }
+            (DynamicImage::ImageRgba8(rgba), detection)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    /// A `width` x `height` page of `background` with rows of short
+    /// `ink` strokes, like lines of text
+    fn page(width: u32, height: u32, background: impl Fn(u32, u32) -> u8, ink: u8) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, y| {
+            if y % 16 < 6 && x % 8 < 3 {
+                Luma([ink])
+            } else {
+                Luma([background(x, y)])
+            }
+        })
+    }
+
+    #[test]
+    fn dark_text_on_light_is_normal() {
+        let img = page(256, 256, |_, _| 235, 20);
+        let detection = detect_polarity(&DynamicImage::ImageLuma8(img));
+        assert_eq!(detection.polarity, Polarity::Normal);
+        assert_eq!(detection.dark_fraction, 0.0);
+    }
+
+    #[test]
+    fn light_text_on_dark_is_inverted() {
+        let img = page(256, 256, |_, _| 30, 220);
+        let detection = detect_polarity(&DynamicImage::ImageLuma8(img));
+        assert_eq!(detection.polarity, Polarity::Inverted);
+        assert_eq!(detection.dark_fraction, 1.0);
+    }
+
+    #[test]
+    fn shadow_over_dark_text_is_normal() {
+        // The left half lies under a lamp shadow darker than mid-gray
+        let img = page(256, 256, |x, _| if x < 128 { 100 } else { 235 }, 10);
+        let detection = detect_polarity(&DynamicImage::ImageLuma8(img));
+        assert_eq!(detection.polarity, Polarity::Normal);
+    }
+
+    #[test]
+    fn mixed_panels_invert_only_light_text() {
+        // A dark sidebar with light text next to a light panel with dark text
+        let img = GrayImage::from_fn(256, 256, |x, y| {
+            let stroke = y % 16 < 6 && x % 8 < 3;
+            match (x < 96, stroke) {
+                (true, true) => Luma([220]),
+                (true, false) => Luma([30]),
+                (false, true) => Luma([20]),
+                (false, false) => Luma([235]),
+            }
+        });
+        let (normalized, detection) = normalize_polarity(&DynamicImage::ImageLuma8(img));
+        assert_eq!(detection.polarity, Polarity::Mixed);
+
+        let normalized = normalized.to_luma8();
+        // Sidebar background and text are flipped, the light panel is not
+        assert_eq!(normalized.get_pixel(4, 10).0[0], 225);
+        assert_eq!(normalized.get_pixel(0, 0).0[0], 35);
+        assert_eq!(normalized.get_pixel(200, 10).0[0], 235);
+        assert_eq!(normalized.get_pixel(200, 0).0[0], 20);
+    }
+
+    #[test]
+    fn dark_page_with_one_line_is_inverted() {
+        // A dark page with one line of light text
+        let img = GrayImage::from_fn(256, 256, |x, y| {
+            if (40..46).contains(&y) && x % 8 < 3 {
+                Luma([220])
+            } else {
+                Luma([30])
+            }
+        });
+        let detection = detect_polarity(&DynamicImage::ImageLuma8(img));
+        assert_eq!(detection.polarity, Polarity::Inverted);
+    }
+
+    #[test]
+    fn unaligned_panel_is_inverted_whole() {
+        // A dark panel at odd offsets, mostly blank, with light text lines
+        // that cross multiples of 32 pixels, above a light area of dark text
+        let in_panel = |x: u32, y: u32| (37..171).contains(&x) && (21..203).contains(&y);
+        let light_stroke = |x: u32, y: u32| {
+            ((60..72).contains(&y) || (124..136).contains(&y))
+                && (61..161).contains(&x)
+                && (x - 61) % 9 < 6
+        };
+        let img = GrayImage::from_fn(256, 256, |x, y| {
+            if in_panel(x, y) {
+                Luma([if light_stroke(x, y) { 220 } else { 30 }])
+            } else if (224..236).contains(&y) && x % 8 < 3 {
+                Luma([20])
+            } else {
+                Luma([235])
+            }
+        });
+        let (normalized, detection) = normalize_polarity(&DynamicImage::ImageLuma8(img.clone()));
+        assert_eq!(detection.polarity, Polarity::Mixed);
+
+        let normalized = normalized.to_luma8();
+        for (x, y, pixel) in img.enumerate_pixels() {
+            let expected = if in_panel(x, y) {
+                255 - pixel.0[0]
+            } else {
+                pixel.0[0]
+            };
+            assert_eq!(normalized.get_pixel(x, y).0[0], expected, "({}, {})", x, y);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Scale normalization
+//!
+//! Tesseract is most accurate when characters are roughly 20-30 pixels
+//! tall. HiDPI screenshots are far larger and thumbnails far smaller, so
+//! the dominant text height is estimated from connected components and
+//! the image resampled to a target height.
+
+use image::imageops::FilterType;
+use image::DynamicImage;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::components::{estimate_character_height, find_components, text_mask};
+use super::transform::ImageTransform;
+
+/// Default target for the dominant text height, in pixels
+pub const DEFAULT_TARGET_TEXT_HEIGHT: f32 = 24.0;
+
+/// Scale factors are clamped to this range
+const MIN_SCALE: f32 = 0.25;
+const MAX_SCALE: f32 = 4.0;
+
+/// Factors this close to 1.0 leave the image unchanged
+const SCALE_TOLERANCE: f32 = 0.15;
+
+/// Largest output image, in pixels
+const MAX_OUTPUT_PIXELS: f32 = 40_000_000.0;
+
+/// Resampling filter for scale normalization
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResampleFilter {
+    /// Lanczos with a 3-lobe window; sharpest
+    #[default]
+    Lanczos,
+    /// Bicubic (Catmull-Rom); slightly softer and faster
+    Bicubic,
+}
+
+impl ResampleFilter {
+    fn filter_type(self) -> FilterType {
+        match self {
+            ResampleFilter::Lanczos => FilterType::Lanczos3,
+            ResampleFilter::Bicubic => FilterType::CatmullRom,
+        }
+    }
+}
+
+/// Estimate the dominant text height of an image
+///
+/// # Arguments
+/// * `img` - The input image
+///
+/// # Returns
+/// The median character height in pixels, or `None` if too little text
+/// was found
+pub fn estimate_text_height(img: &DynamicImage) -> Option<f32> {
+    let mask = text_mask(&img.to_luma8());
+    let (_, components) = find_components(&mask);
+    estimate_character_height(&components, img.height())
+}
+
+/// Scale factor bringing `text_height` to `target_height`
+///
+/// The factor is clamped to 0.25-4.0, limited so the output stays below
+/// 40 megapixels, and snapped to 1.0 when the change would be small.
+///
+/// # Arguments
+/// * `text_height` - Estimated text height in pixels
+/// * `target_height` - Desired text height in pixels
+/// * `width`, `height` - Image dimensions
+pub fn normalization_factor(text_height: f32, target_height: f32, width: u32, height: u32) -> f32 {
+    let max_factor = (MAX_OUTPUT_PIXELS / (width as f32 * height as f32)).sqrt();
+    let factor = (target_height / text_height)
+        .clamp(MIN_SCALE, MAX_SCALE)
+        .min(max_factor);
+    if (factor - 1.0).abs() < SCALE_TOLERANCE {
+        1.0
+    } else {
+        factor
+    }
+}
+
+/// Resample an image by a scale factor
+///
+/// # Arguments
+/// * `img` - The input image
+/// * `factor` - Scale factor; 1.0 leaves the image unchanged
+/// * `filter` - Resampling filter
+///
+/// # Returns
+/// The resampled image and the scaling that was applied, if any
+pub fn rescale(
+    img: &DynamicImage,
+    factor: f32,
+    filter: ResampleFilter,
+) -> (DynamicImage, Option<ImageTransform>) {
+    let (width, height) = (img.width(), img.height());
+    let new_width = ((width as f32 * factor).round() as u32).max(1);
+    let new_height = ((height as f32 * factor).round() as u32).max(1);
+    if new_width == width && new_height == height {
+        return (img.clone(), None);
+    }
+
+    log::debug!(
+        "[Scale] Resampling {}x{} -> {}x{} ({:?})",
+        width,
+        height,
+        new_width,
+        new_height,
+        filter
+    );
+    let resized = img.resize_exact(new_width, new_height, filter.filter_type());
+    let transform = ImageTransform::Scale {
+        factor_x: new_width as f32 / width as f32,
+        factor_y: new_height as f32 / height as f32,
+    };
+    (resized, Some(transform))
+}
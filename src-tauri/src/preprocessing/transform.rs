@@ -0,0 +1,321 @@
+//! Coordinate bookkeeping for geometric preprocessing
+//!
+//! Geometric steps (perspective, border cropping, orientation, deskewing,
+//! dewarping, scaling) change the pixel grid that Tesseract sees. Each step
+//! records an `ImageTransform` so that positions reported on the processed
+//! image can be mapped back to the source image.
+
+use imageproc::geometric_transformations::Projection;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::dewarp::DisplacementField;
+
+/// Axis-aligned bounding box in pixel coordinates
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BoundingBox {
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl BoundingBox {
+    pub fn right(&self) -> u32 {
+        self.left + self.width
+    }
+
+    pub fn bottom(&self) -> u32 {
+        self.top + self.height
+    }
+}
+
+/// A single geometric operation applied to the image
+#[derive(Debug, Clone)]
+pub enum ImageTransform {
+    /// The image was cropped; `(left, top)` is the origin of the kept region
+    Crop { left: u32, top: u32 },
+    /// The image was rotated clockwise by `radians` about `(center_x, center_y)`
+    /// on a canvas of unchanged size (see `rotate_about_center`)
+    Rotate {
+        radians: f32,
+        center_x: f32,
+        center_y: f32,
+    },
+    /// The image was rotated clockwise by `turns` quarter turns; `width` and
+    /// `height` are the dimensions before rotation
+    QuarterTurn { turns: u32, width: u32, height: u32 },
+    /// The image was resampled by `factor_x` horizontally and `factor_y`
+    /// vertically
+    Scale { factor_x: f32, factor_y: f32 },
+    /// A quadrilateral was warped to a rectangle; `to_source` maps the
+    /// rectangle back onto the quadrilateral
+    Perspective { to_source: Projection },
+    /// Curved text lines were straightened by a vertical displacement field
+    Dewarp(Arc<DisplacementField>),
+}
+
+impl ImageTransform {
+    /// Map a point on the transformed image back to the image before this step
+    fn invert_point(&self, x: f32, y: f32) -> (f32, f32) {
+        match *self {
+            ImageTransform::Crop { left, top } => (x + left as f32, y + top as f32),
+            ImageTransform::Rotate {
+                radians,
+                center_x,
+                center_y,
+            } => {
+                let (sin, cos) = (-radians).sin_cos();
+                let dx = x - center_x;
+                let dy = y - center_y;
+                (
+                    center_x + dx * cos - dy * sin,
+                    center_y + dx * sin + dy * cos,
+                )
+            }
+            ImageTransform::QuarterTurn {
+                turns,
+                width,
+                height,
+            } => match turns % 4 {
+                1 => (y, height as f32 - x),
+                2 => (width as f32 - x, height as f32 - y),
+                3 => (width as f32 - y, x),
+                _ => (x, y),
+            },
+            ImageTransform::Scale { factor_x, factor_y } => (x / factor_x, y / factor_y),
+            ImageTransform::Perspective { to_source } => to_source * (x, y),
+            ImageTransform::Dewarp(ref field) => field.to_curved(x, y),
+        }
+    }
+}
+
+/// Maps coordinates on the processed image back to the source image
+#[derive(Debug, Clone)]
+pub struct CoordinateMapper {
+    source_width: u32,
+    source_height: u32,
+    transforms: Vec<ImageTransform>,
+}
+
+impl CoordinateMapper {
+    /// Create an identity mapper for a source image of the given size
+    pub fn new(source_width: u32, source_height: u32) -> Self {
+        Self {
+            source_width,
+            source_height,
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Map a point on the processed image to source image coordinates
+    pub fn to_source(&self, x: f32, y: f32) -> (f32, f32) {
+        self.transforms
+            .iter()
+            .rev()
+            .fold((x, y), |(x, y), transform| transform.invert_point(x, y))
+    }
+
+    /// Map a box on the processed image to the axis-aligned box enclosing
+    /// its corners in source image coordinates, clamped to the source bounds
+    pub fn map_box(&self, bbox: BoundingBox) -> BoundingBox {
+        let corners = [
+            (bbox.left as f32, bbox.top as f32),
+            (bbox.right() as f32, bbox.top as f32),
+            (bbox.left as f32, bbox.bottom() as f32),
+            (bbox.right() as f32, bbox.bottom() as f32),
+        ];
+
+        let mut min_x = f32::MAX;
+        let mut min_y = f32::MAX;
+        let mut max_x = f32::MIN;
+        let mut max_y = f32::MIN;
+        for (x, y) in corners {
+            let (sx, sy) = self.to_source(x, y);
+            min_x = min_x.min(sx);
+            min_y = min_y.min(sy);
+            max_x = max_x.max(sx);
+            max_y = max_y.max(sy);
+        }
+
+        let max_w = self.source_width as f32;
+        let max_h = self.source_height as f32;
+        let left = min_x.round().clamp(0.0, max_w) as u32;
+        let top = min_y.round().clamp(0.0, max_h) as u32;
+        let right = max_x.round().clamp(0.0, max_w) as u32;
+        let bottom = max_y.round().clamp(0.0, max_h) as u32;
+
+        BoundingBox {
+            left,
+            top,
+            width: right.saturating_sub(left),
+            height: bottom.saturating_sub(top),
+        }
+    }
+}
+
+/// Records transforms applied after all previously recorded ones
+impl Extend<ImageTransform> for CoordinateMapper {
+    fn extend<I: IntoIterator<Item = ImageTransform>>(&mut self, iter: I) {
+        self.transforms.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{imageops, GrayImage, Luma};
+
+    fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-3 && (actual.1 - expected.1).abs() < 1e-3,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    fn mapper_with(transforms: Vec<ImageTransform>) -> CoordinateMapper {
+        let mut mapper = CoordinateMapper::new(200, 100);
+        mapper.extend(transforms);
+        mapper
+    }
+
+    /// Position of the only bright pixel of an image
+    fn marker(img: &GrayImage) -> (u32, u32) {
+        let (x, y, _) = img
+            .enumerate_pixels()
+            .find(|(_, _, p)| p.0[0] == 255)
+            .expect("marker pixel");
+        (x, y)
+    }
+
+    #[test]
+    fn identity_maps_points_unchanged() {
+        let mapper = CoordinateMapper::new(200, 100);
+        assert_close(mapper.to_source(12.5, 40.0), (12.5, 40.0));
+    }
+
+    #[test]
+    fn crop_round_trip() {
+        let mapper = mapper_with(vec![ImageTransform::Crop { left: 15, top: 7 }]);
+        // Source (40, 30) lands at (25, 23) on the cropped image
+        assert_close(mapper.to_source(25.0, 23.0), (40.0, 30.0));
+    }
+
+    #[test]
+    fn rotate_round_trip() {
+        let (center_x, center_y) = (100.0, 50.0);
+        for degrees in [-7.5f32, 3.0, 45.0] {
+            let radians = degrees.to_radians();
+            let mapper = mapper_with(vec![ImageTransform::Rotate {
+                radians,
+                center_x,
+                center_y,
+            }]);
+            // Rotate the source point clockwise about the center
+            let (x, y) = (130.0f32, 20.0f32);
+            let (sin, cos) = radians.sin_cos();
+            let (dx, dy) = (x - center_x, y - center_y);
+            let rotated = (
+                center_x + dx * cos - dy * sin,
+                center_y + dx * sin + dy * cos,
+            );
+            assert_close(mapper.to_source(rotated.0, rotated.1), (x, y));
+        }
+    }
+
+    #[test]
+    fn quarter_turn_matches_image_rotation() {
+        let (width, height) = (7, 4);
+        let mut img = GrayImage::new(width, height);
+        img.put_pixel(5, 1, Luma([255]));
+
+        for turns in 0..4 {
+            let rotated = match turns {
+                1 => imageops::rotate90(&img),
+                2 => imageops::rotate180(&img),
+                3 => imageops::rotate270(&img),
+                _ => img.clone(),
+            };
+            let mut mapper = CoordinateMapper::new(width, height);
+            mapper.extend([ImageTransform::QuarterTurn {
+                turns,
+                width,
+                height,
+            }]);
+            // Pixel centers map onto pixel centers
+            let (x, y) = marker(&rotated);
+            assert_close(mapper.to_source(x as f32 + 0.5, y as f32 + 0.5), (5.5, 1.5));
+        }
+    }
+
+    #[test]
+    fn scale_round_trip() {
+        let mapper = mapper_with(vec![ImageTransform::Scale {
+            factor_x: 2.0,
+            factor_y: 0.5,
+        }]);
+        assert_close(mapper.to_source(60.0, 10.0), (30.0, 20.0));
+    }
+
+    #[test]
+    fn transforms_are_inverted_in_reverse_order() {
+        // Crop first, then upscale: undo the scale, then the crop
+        let mapper = mapper_with(vec![
+            ImageTransform::Crop { left: 10, top: 20 },
+            ImageTransform::Scale {
+                factor_x: 2.0,
+                factor_y: 2.0,
+            },
+        ]);
+        assert_close(mapper.to_source(50.0, 30.0), (35.0, 35.0));
+    }
+
+    #[test]
+    fn map_box_encloses_rotated_corners_and_clamps() {
+        let mapper = mapper_with(vec![ImageTransform::QuarterTurn {
+            turns: 1,
+            width: 200,
+            height: 100,
+        }]);
+        // The rotated image is 100 x 200; a box near its top-left corner
+        // comes from the bottom-left of the source
+        let bbox = mapper.map_box(BoundingBox {
+            left: 10,
+            top: 20,
+            width: 30,
+            height: 5,
+        });
+        assert_eq!(
+            bbox,
+            BoundingBox {
+                left: 20,
+                top: 60,
+                width: 5,
+                height: 30,
+            }
+        );
+
+        let scaled = mapper_with(vec![ImageTransform::Scale {
+            factor_x: 0.5,
+            factor_y: 0.5,
+        }]);
+        let clamped = scaled.map_box(BoundingBox {
+            left: 90,
+            top: 40,
+            width: 20,
+            height: 20,
+        });
+        assert_eq!(
+            clamped,
+            BoundingBox {
+                left: 180,
+                top: 80,
+                width: 20,
+                height: 20,
+            }
+        );
+    }
+}
@@ -0,0 +1,466 @@
+//! Processing parameter presets
+//!
+//! Built-in presets cover common input types (screenshots, scans,
+//! whiteboard photos, receipts, dark-mode UIs). User presets are stored as
+//! a JSON array in the application config directory and can be imported
+//! from and exported to files for sharing.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::binarization::{BinarizationMethod, DEFAULT_CLAHE_CLIP_LIMIT, DEFAULT_CLAHE_TILE_GRID};
+use crate::morphology::MorphologyOperation;
+use crate::ocr::{PageSegmentationMode, ProcessingParams, TesseractOptions};
+use crate::preprocessing::SkewMethod;
+
+/// Preset used for interactive screenshots
+pub const SCREENSHOT_PRESET: &str = "screenshot";
+
+/// File holding user presets inside the config directory
+const PRESETS_FILE: &str = "presets.json";
+
+/// Longest accepted preset name
+const MAX_NAME_LENGTH: usize = 64;
+
+/// A named set of processing parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Built-in presets cannot be overwritten or deleted
+    #[serde(default, skip_deserializing)]
+    pub built_in: bool,
+    pub params: ProcessingParams,
+}
+
+/// Defaults shared by the built-in presets
+fn base_params() -> ProcessingParams {
+    ProcessingParams {
+        contrast: 1.3,
+        brightness: 0.0,
+        sharpness: 1.2,
+        binarization_method: BinarizationMethod::Otsu,
+        threshold_window: None,
+        threshold_k: None,
+        threshold_r: None,
+        use_clahe: true,
+        clahe_tile_grid: DEFAULT_CLAHE_TILE_GRID,
+        clahe_clip_limit: DEFAULT_CLAHE_CLIP_LIMIT,
+        gaussian_blur: 0.5,
+        bilateral_filter: false,
+        morphology: MorphologyOperation::None,
+        language: "eng".to_string(),
+        correct_skew: true,
+        skew_method: SkewMethod::Projection,
+        correct_perspective: false,
+        document_corners: None,
+        dewarp: false,
+        remove_borders: true,
+        adaptive_mode: true,
+        detect_orientation: false,
+        normalize_scale: false,
+        target_text_height: None,
+        normalize_polarity: false,
+        color_dropout: false,
+        normalize_background: false,
+        remove_lines: false,
+        return_table_grid: false,
+        detect_tables: false,
+        despeckle: false,
+        despeckle_border: false,
+        pdf_dpi: None,
+        tesseract: TesseractOptions::default(),
+        pipeline: None,
+        debug: false,
+        best_of: false,
+    }
+}
+
+/// Tesseract options with only the page segmentation mode set
+fn with_page_seg_mode(mode: u8) -> TesseractOptions {
+    TesseractOptions {
+        page_seg_mode: PageSegmentationMode::try_from(mode).ok(),
+        ..TesseractOptions::default()
+    }
+}
+
+fn built_in(name: &str, description: &str, params: ProcessingParams) -> Preset {
+    Preset {
+        name: name.to_string(),
+        description: description.to_string(),
+        built_in: true,
+        params,
+    }
+}
+
+/// Presets shipped with the application
+pub fn built_in_presets() -> Vec<Preset> {
+    vec![
+        built_in(
+            SCREENSHOT_PRESET,
+            "Screen captures with crisp, evenly lit text",
+            base_params(),
+        ),
+        built_in(
+            "scanned document",
+            "Flatbed or sheet-fed scans of printed pages",
+            ProcessingParams {
+                contrast: 1.0,
+                sharpness: 1.0,
+                binarization_method: BinarizationMethod::Sauvola,
+                use_clahe: false,
+                gaussian_blur: 0.0,
+                adaptive_mode: false,
+                detect_orientation: true,
+                ..base_params()
+            },
+        ),
+        built_in(
+            "photo of whiteboard",
+            "Camera photos with glare and uneven lighting",
+            ProcessingParams {
+                contrast: 1.2,
+                sharpness: 1.5,
+                binarization_method: BinarizationMethod::Sauvola,
+                threshold_window: Some(51),
+                threshold_k: Some(0.2),
+                clahe_clip_limit: 3.0,
+                bilateral_filter: true,
+                skew_method: SkewMethod::Hough,
+                correct_perspective: true,
+                normalize_background: true,
+                remove_borders: false,
+                adaptive_mode: false,
+                tesseract: with_page_seg_mode(11),
+                ..base_params()
+            },
+        ),
+        built_in(
+            "receipt",
+            "Narrow single-column thermal prints with faint text",
+            ProcessingParams {
+                contrast: 1.5,
+                sharpness: 1.5,
+                binarization_method: BinarizationMethod::Sauvola,
+                threshold_window: Some(25),
+                threshold_k: Some(0.2),
+                gaussian_blur: 0.0,
+                adaptive_mode: false,
+                detect_orientation: true,
+                correct_perspective: true,
+                normalize_background: true,
+                tesseract: with_page_seg_mode(4),
+                ..base_params()
+            },
+        ),
+        built_in(
+            "dark-mode UI",
+            "Screenshots of light text on dark backgrounds",
+            ProcessingParams {
+                contrast: 1.2,
+                sharpness: 1.0,
+                use_clahe: false,
+                gaussian_blur: 0.0,
+                correct_skew: false,
+                remove_borders: false,
+                adaptive_mode: false,
+                normalize_polarity: true,
+                tesseract: with_page_seg_mode(11),
+                ..base_params()
+            },
+        ),
+    ]
+}
+
+fn is_built_in(name: &str) -> bool {
+    built_in_presets()
+        .iter()
+        .any(|preset| preset.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Check a user preset before it is stored
+fn validate_preset(preset: &Preset) -> Result<(), String> {
+    let name = preset.name.trim();
+    if name.is_empty() {
+        return Err("Preset name must not be empty".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!(
+            "Preset name is too long (at most {} characters)",
+            MAX_NAME_LENGTH
+        ));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("Preset name must not contain control characters".to_string());
+    }
+    if is_built_in(name) {
+        return Err(format!("Cannot overwrite built-in preset '{}'", name));
+    }
+    preset
+        .params
+        .validate()
+        .map_err(|e| format!("Preset '{}': {}", name, e))
+}
+
+/// Merge `patch` into `target` following JSON Merge Patch (RFC 7396)
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(Default::default());
+    }
+    let serde_json::Value::Object(target) = target else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(
+                target.entry(key.clone()).or_insert(serde_json::Value::Null),
+                value,
+            );
+        }
+    }
+}
+
+/// Apply per-field overrides to processing parameters
+///
+/// # Arguments
+/// * `params` - Parameters of the selected preset
+/// * `overrides` - Partial parameters in the same camelCase shape; `null`
+///   resets an optional field to its default
+///
+/// # Returns
+/// The merged parameters, with enums and field types checked again
+pub fn apply_overrides(
+    params: &ProcessingParams,
+    overrides: &serde_json::Value,
+) -> Result<ProcessingParams, String> {
+    if !overrides.is_object() {
+        return Err("Preset overrides must be an object".to_string());
+    }
+    let mut value = serde_json::to_value(params)
+        .map_err(|e| format!("Failed to serialize preset parameters: {}", e))?;
+    merge_patch(&mut value, overrides);
+    serde_json::from_value(value).map_err(|e| format!("Invalid preset overrides: {}", e))
+}
+
+/// Built-in and user presets backed by a file in the config directory
+pub struct PresetStore {
+    path: PathBuf,
+}
+
+impl PresetStore {
+    /// # Arguments
+    /// * `config_dir` - Application config directory holding the presets file
+    pub fn new(config_dir: &Path) -> Self {
+        Self {
+            path: config_dir.join(PRESETS_FILE),
+        }
+    }
+
+    /// User presets, or none when the file does not exist yet
+    fn load_user(&self) -> Result<Vec<Preset>, String> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let data =
+            fs::read_to_string(&self.path).map_err(|e| format!("Failed to read presets: {}", e))?;
+        serde_json::from_str(&data).map_err(|e| format!("Failed to parse presets: {}", e))
+    }
+
+    fn store_user(&self, presets: &[Preset]) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let data = serde_json::to_string_pretty(presets)
+            .map_err(|e| format!("Failed to serialize presets: {}", e))?;
+        fs::write(&self.path, data).map_err(|e| format!("Failed to save presets: {}", e))
+    }
+
+    /// Built-in presets followed by user presets
+    ///
+    /// An unreadable presets file is logged and skipped so the built-in
+    /// presets stay available.
+    pub fn list(&self) -> Vec<Preset> {
+        let mut presets = built_in_presets();
+        match self.load_user() {
+            Ok(user) => presets.extend(user),
+            Err(e) => log::warn!("[Presets] Skipping user presets: {}", e),
+        }
+        presets
+    }
+
+    /// Look up a preset by name, ignoring case
+    ///
+    /// Built-in names resolve without reading the presets file.
+    pub fn get(&self, name: &str) -> Result<Preset, String> {
+        let name = name.trim();
+        if let Some(preset) = built_in_presets()
+            .into_iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(name))
+        {
+            return Ok(preset);
+        }
+        self.load_user()?
+            .into_iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Unknown preset: {}", name))
+    }
+
+    /// Add user presets, replacing any with the same name
+    ///
+    /// # Returns
+    /// The number of presets stored
+    pub fn save(&self, presets: Vec<Preset>) -> Result<usize, String> {
+        for preset in &presets {
+            validate_preset(preset)?;
+        }
+
+        let mut stored = self.load_user()?;
+        let count = presets.len();
+        for mut preset in presets {
+            preset.name = preset.name.trim().to_string();
+            preset.built_in = false;
+            stored.retain(|existing| !existing.name.eq_ignore_ascii_case(&preset.name));
+            stored.push(preset);
+        }
+        self.store_user(&stored)?;
+        Ok(count)
+    }
+
+    /// Remove a user preset
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        if is_built_in(name) {
+            return Err(format!("Cannot delete built-in preset '{}'", name));
+        }
+        let mut stored = self.load_user()?;
+        let before = stored.len();
+        stored.retain(|preset| !preset.name.eq_ignore_ascii_case(name.trim()));
+        if stored.len() == before {
+            return Err(format!("Unknown preset: {}", name));
+        }
+        self.store_user(&stored)
+    }
+
+    /// Store presets from a file holding a single preset or an array
+    ///
+    /// # Returns
+    /// The number of presets imported
+    pub fn import(&self, input_path: &Path) -> Result<usize, String> {
+        let data = fs::read_to_string(input_path)
+            .map_err(|e| format!("Failed to read preset file: {}", e))?;
+        let value: serde_json::Value = serde_json::from_str(&data)
+            .map_err(|e| format!("Failed to parse preset file: {}", e))?;
+        let presets = if value.is_array() {
+            serde_json::from_value(value)
+        } else {
+            serde_json::from_value(value).map(|preset| vec![preset])
+        }
+        .map_err(|e| format!("Invalid preset file: {}", e))?;
+        self.save(presets)
+    }
+
+    /// Write the named presets (all user presets when empty) to a file
+    ///
+    /// Built-in presets cannot be exported: every installation already has
+    /// them, and importing them back would clash with the originals.
+    pub fn export(&self, names: &[String], output_path: &Path) -> Result<(), String> {
+        if let Some(name) = names.iter().find(|name| is_built_in(name)) {
+            return Err(format!("Cannot export built-in preset '{}'", name.trim()));
+        }
+        let presets = if names.is_empty() {
+            self.load_user()?
+        } else {
+            names
+                .iter()
+                .map(|name| self.get(name))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let data = serde_json::to_string_pretty(&presets)
+            .map_err(|e| format!("Failed to serialize presets: {}", e))?;
+        fs::write(output_path, data).map_err(|e| format!("Failed to export presets: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Store in a fresh temporary config directory
+    fn store(name: &str) -> (PresetStore, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("imagio_presets_{}", name));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        (PresetStore::new(&dir), dir)
+    }
+
+    fn user_preset(name: &str) -> Preset {
+        Preset {
+            name: name.to_string(),
+            description: "Mine".to_string(),
+            built_in: false,
+            params: ProcessingParams {
+                contrast: 1.7,
+                ..base_params()
+            },
+        }
+    }
+
+    #[test]
+    fn built_ins_survive_a_corrupt_presets_file() {
+        let (store, dir) = store("corrupt");
+        fs::write(dir.join(PRESETS_FILE), "[{\"name\": \"half written").unwrap();
+
+        assert_eq!(
+            store.get(SCREENSHOT_PRESET).unwrap().name,
+            SCREENSHOT_PRESET
+        );
+        assert_eq!(store.list().len(), built_in_presets().len());
+        assert!(store.get("mine").is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn exported_presets_import_back() {
+        let (store, dir) = store("round_trip");
+        store.save(vec![user_preset("Mine")]).unwrap();
+        let file = dir.join("export.json");
+        store.export(&["mine".to_string()], &file).unwrap();
+
+        let (other, other_dir) = store_at(&dir, "other");
+        assert_eq!(other.import(&file).unwrap(), 1);
+        let imported = other.get("Mine").unwrap();
+        assert!(!imported.built_in);
+        assert_eq!(imported.params.contrast, 1.7);
+        fs::remove_dir_all(&other_dir).ok();
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn built_ins_cannot_be_exported() {
+        let (store, dir) = store("export_built_in");
+        let file = dir.join("export.json");
+        let result = store.export(&["Screenshot".to_string()], &file);
+        assert_eq!(
+            result,
+            Err("Cannot export built-in preset 'Screenshot'".to_string())
+        );
+        assert!(!file.exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    /// A second store in a subdirectory of `dir`
+    fn store_at(dir: &Path, name: &str) -> (PresetStore, PathBuf) {
+        let sub = dir.join(name);
+        fs::create_dir_all(&sub).unwrap();
+        (PresetStore::new(&sub), sub)
+    }
+}
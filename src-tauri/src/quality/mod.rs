@@ -5,10 +5,14 @@
 //! - Contrast measurement (standard deviation)
 //! - Noise estimation (local variance)
 //! - Brightness analysis
+//! - Text polarity (dark mode) detection
+//! - Illumination unevenness (shadows, gradients)
 
 use image::DynamicImage;
 use serde::Serialize;
 
+use crate::preprocessing::{detect_polarity, measure_illumination_unevenness, PolarityDetection};
+
 /// Image quality metrics for adaptive preprocessing
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -21,6 +25,10 @@ pub struct ImageQualityMetrics {
     pub noise_level: f32,
     /// Average brightness (0-255)
     pub brightness_level: f32,
+    /// Whether text is light on dark, globally or in some regions
+    pub polarity: PolarityDetection,
+    /// Background brightness spread (0-100, higher means shadows or gradients)
+    pub illumination_unevenness: f32,
 }
 
 /// Assess image quality for adaptive preprocessing
@@ -32,7 +40,8 @@ pub struct ImageQualityMetrics {
 /// * `img` - The input image
 ///
 /// # Returns
-/// Quality metrics including blur, contrast, noise, and brightness scores
+/// Quality metrics including blur, contrast, noise, brightness, polarity
+/// and illumination unevenness
 pub fn assess_image_quality(img: &DynamicImage) -> ImageQualityMetrics {
     let gray = img.to_luma8();
     let (width, height) = gray.dimensions();
@@ -110,5 +119,7 @@ pub fn assess_image_quality(img: &DynamicImage) -> ImageQualityMetrics {
         contrast_score,
         noise_level,
         brightness_level: mean,
+        polarity: detect_polarity(img),
+        illumination_unevenness: measure_illumination_unevenness(img),
     }
 }
@@ -0,0 +1,304 @@
+//! Table export formats
+//!
+//! Renders recognized tables as CSV, Markdown or an XLSX workbook. CSV and
+//! Markdown have no merged cells, so a merged cell's text goes in its
+//! top-left position and the positions it covers are left empty; XLSX
+//! records the merges.
+
+use serde::Deserialize;
+use std::fmt::Write;
+
+use super::zip::ZipWriter;
+use super::Table;
+use crate::ocr::escape_xml;
+
+/// Export format for tables
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    /// Comma-separated values; tables separated by a blank line
+    Csv,
+    /// GitHub-flavoured Markdown tables; the first row is the header
+    Markdown,
+    /// Excel workbook with one sheet per table
+    Xlsx,
+}
+
+impl TableFormat {
+    /// Conventional file extension for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Markdown => "md",
+            TableFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// Render tables in the requested format
+///
+/// An XLSX workbook needs at least one sheet, so callers should not pass
+/// an empty list for that format.
+pub fn render_tables(format: TableFormat, tables: &[Table]) -> Vec<u8> {
+    match format {
+        TableFormat::Csv => render_csv(tables).into_bytes(),
+        TableFormat::Markdown => render_markdown(tables).into_bytes(),
+        TableFormat::Xlsx => render_xlsx(tables),
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Render RFC 4180 CSV
+fn render_csv(tables: &[Table]) -> String {
+    tables
+        .iter()
+        .map(|table| {
+            table
+                .to_matrix()
+                .iter()
+                .map(|row| {
+                    let fields: Vec<String> = row.iter().map(|text| csv_field(text)).collect();
+                    fields.join(",") + "\r\n"
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+/// Escape a Markdown table cell
+fn markdown_cell(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], "<br>")
+}
+
+/// Render Markdown pipe tables
+fn render_markdown(tables: &[Table]) -> String {
+    let mut out = String::new();
+    for (index, table) in tables.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        for (row_index, row) in table.to_matrix().iter().enumerate() {
+            let cells: Vec<String> = row.iter().map(|text| markdown_cell(text)).collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+            if row_index == 0 {
+                let _ = writeln!(out, "|{}", " --- |".repeat(table.columns));
+            }
+        }
+    }
+    out
+}
+
+/// Spreadsheet column name: A-Z, then AA, AB, ...
+fn column_name(mut column: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (column % 26) as u8);
+        if column < 26 {
+            break;
+        }
+        column = column / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Largest number of significant digits a spreadsheet number keeps
+const MAX_SIGNIFICANT_DIGITS: usize = 15;
+
+/// Whether cell text can be stored as a number without changing it
+///
+/// Codes with leading zeros ("007") and long digit strings such as account
+/// numbers would lose digits as numbers, so they stay text.
+fn is_numeric_cell(text: &str) -> bool {
+    if !text.parse::<f64>().is_ok_and(f64::is_finite) {
+        return false;
+    }
+    let unsigned = text.trim_start_matches(['+', '-']);
+    let mantissa = unsigned.split(['e', 'E']).next().unwrap_or("");
+    let bytes = mantissa.as_bytes();
+    if bytes.len() > 1 && bytes[0] == b'0' && bytes[1].is_ascii_digit() {
+        return false;
+    }
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let significant = digits.trim_start_matches('0').trim_end_matches('0');
+    significant.len() <= MAX_SIGNIFICANT_DIGITS
+}
+
+/// SpreadsheetML for one sheet
+///
+/// Numbers are written as numeric cells so spreadsheet formulas work on
+/// them; all other text, including numbers a spreadsheet would alter, is
+/// written as inline strings.
+fn sheet_xml(table: &Table) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
+    out.push_str("<worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">");
+    out.push_str("<sheetData>");
+    for (r, row) in table.to_matrix().iter().enumerate() {
+        let _ = write!(out, "<row r=\"{}\">", r + 1);
+        for (c, text) in row.iter().enumerate() {
+            if text.is_empty() {
+                continue;
+            }
+            let reference = format!("{}{}", column_name(c), r + 1);
+            let trimmed = text.trim();
+            if is_numeric_cell(trimmed) {
+                let _ = write!(out, "<c r=\"{}\"><v>{}</v></c>", reference, trimmed);
+            } else {
+                let _ = write!(
+                    out,
+                    "<c r=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                    reference,
+                    escape_xml(text)
+                );
+            }
+        }
+        out.push_str("</row>");
+    }
+    out.push_str("</sheetData>");
+
+    let merged: Vec<String> = table
+        .cells
+        .iter()
+        .filter(|cell| cell.row_span > 1 || cell.column_span > 1)
+        .map(|cell| {
+            format!(
+                "<mergeCell ref=\"{}{}:{}{}\"/>",
+                column_name(cell.column),
+                cell.row + 1,
+                column_name(cell.column + cell.column_span - 1),
+                cell.row + cell.row_span
+            )
+        })
+        .collect();
+    if !merged.is_empty() {
+        let _ = write!(
+            out,
+            "<mergeCells count=\"{}\">{}</mergeCells>",
+            merged.len(),
+            merged.concat()
+        );
+    }
+    out.push_str("</worksheet>");
+    out
+}
+
+/// Package the tables as an XLSX workbook, one sheet per table
+fn render_xlsx(tables: &[Table]) -> Vec<u8> {
+    const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+    const MAIN_NS: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+    const REL_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+    const PACKAGE_REL_NS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+
+    let mut content_types = String::from(XML_HEADER);
+    content_types.push_str(
+        "<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+         <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+         <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+         <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>",
+    );
+    let mut sheets = String::new();
+    let mut workbook_rels = String::new();
+    for index in 1..=tables.len() {
+        let _ = write!(
+            content_types,
+            "<Override PartName=\"/xl/worksheets/sheet{}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+            index
+        );
+        let _ = write!(
+            sheets,
+            "<sheet name=\"Table {0}\" sheetId=\"{0}\" r:id=\"rId{0}\"/>",
+            index
+        );
+        let _ = write!(
+            workbook_rels,
+            "<Relationship Id=\"rId{0}\" Type=\"{1}/worksheet\" Target=\"worksheets/sheet{0}.xml\"/>",
+            index, REL_NS
+        );
+    }
+    content_types.push_str("</Types>");
+
+    let root_rels = format!(
+        "{}<Relationships xmlns=\"{}\"><Relationship Id=\"rId1\" Type=\"{}/officeDocument\" Target=\"xl/workbook.xml\"/></Relationships>",
+        XML_HEADER, PACKAGE_REL_NS, REL_NS
+    );
+    let workbook = format!(
+        "{}<workbook xmlns=\"{}\" xmlns:r=\"{}\"><sheets>{}</sheets></workbook>",
+        XML_HEADER, MAIN_NS, REL_NS, sheets
+    );
+    let workbook_rels = format!(
+        "{}<Relationships xmlns=\"{}\">{}</Relationships>",
+        XML_HEADER, PACKAGE_REL_NS, workbook_rels
+    );
+
+    let mut zip = ZipWriter::new();
+    zip.add_file("[Content_Types].xml", content_types.as_bytes());
+    zip.add_file("_rels/.rels", root_rels.as_bytes());
+    zip.add_file("xl/workbook.xml", workbook.as_bytes());
+    zip.add_file("xl/_rels/workbook.xml.rels", workbook_rels.as_bytes());
+    for (index, table) in tables.iter().enumerate() {
+        zip.add_file(
+            &format!("xl/worksheets/sheet{}.xml", index + 1),
+            sheet_xml(table).as_bytes(),
+        );
+    }
+    zip.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_numbers_are_numeric() {
+        for text in [
+            "0",
+            "42",
+            "-3.5",
+            "0.25",
+            "+7",
+            "1e6",
+            "1000000000000000000",
+        ] {
+            assert!(is_numeric_cell(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn numbers_a_spreadsheet_would_alter_stay_text() {
+        for text in [
+            "007",
+            "-0042",
+            "00.5",
+            "1234567890123456",
+            "4111111111111111",
+            "0.1234567890123456",
+            "NaN",
+            "inf",
+            "12 kg",
+            "",
+        ] {
+            assert!(!is_numeric_cell(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn column_names_continue_past_z() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+}
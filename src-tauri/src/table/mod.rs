@@ -0,0 +1,790 @@
+//! Table structure recognition
+//!
+//! Rebuilds tables from the recognized word boxes so they can be exported
+//! cell by cell instead of as one text blob:
+//! - Ruled tables come from the grid found by line removal: line
+//!   positions give the rows and columns, and missing line segments
+//!   between neighbouring cells mark merged cells
+//! - Unruled tables come from word alignment: text lines at the same height
+//!   within a layout block form rows, wide gaps split rows into cells, and
+//!   gaps shared by most rows give the columns; cells crossing a column gap
+//!   span several columns. Lines of separate blocks are never joined, so
+//!   side-by-side text columns are not read as a table
+//! - CSV, Markdown and XLSX export
+
+mod export;
+mod zip;
+
+pub use export::{render_tables, TableFormat};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ocr::{OcrBlock, OcrWord};
+use crate::preprocessing::{BoundingBox, TableGrid};
+
+/// Distance within which ruled lines are taken to meet or coincide, in
+/// pixels
+const LINE_TOLERANCE: u32 = 8;
+
+/// Gap between words that separates cells, in word heights
+const CELL_GAP: f32 = 1.2;
+
+/// Largest gap between consecutive rows of an unruled table, in row heights
+const MAX_ROW_GAP: f32 = 2.5;
+
+/// Share of the rows that may cross a column gap (spanning cells)
+const SPANNING_ROW_SHARE: f32 = 0.25;
+
+/// Fewest rows and columns of a table
+const MIN_ROWS: usize = 2;
+const MIN_COLUMNS: usize = 2;
+
+/// Fewest rows that must share a column of an unruled table
+const MIN_ALIGNED_ROWS: usize = 3;
+
+/// One cell of a table; merged cells span several rows or columns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableCell {
+    /// 0-based index of the top row the cell covers
+    pub row: usize,
+    /// 0-based index of the leftmost column the cell covers
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    pub text: String,
+    pub bbox: BoundingBox,
+}
+
+/// A table recognized on a page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Table {
+    pub rows: usize,
+    pub columns: usize,
+    pub bbox: BoundingBox,
+    /// Whether the structure came from ruled lines rather than alignment
+    pub ruled: bool,
+    /// Cells in row-major order; together they cover every position once
+    pub cells: Vec<TableCell>,
+}
+
+impl Table {
+    /// Cell texts as a rows × columns matrix, with each merged cell's text
+    /// in its top-left position and the positions it covers left empty
+    pub fn to_matrix(&self) -> Vec<Vec<String>> {
+        let mut matrix = vec![vec![String::new(); self.columns]; self.rows];
+        for cell in &self.cells {
+            if let Some(slot) = matrix
+                .get_mut(cell.row)
+                .and_then(|row| row.get_mut(cell.column))
+            {
+                slot.clone_from(&cell.text);
+            }
+        }
+        matrix
+    }
+}
+
+/// Union-find root of `i`, compressing the path on the way
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+fn center_x(bbox: &BoundingBox) -> u32 {
+    bbox.left + bbox.width / 2
+}
+
+fn center_y(bbox: &BoundingBox) -> u32 {
+    bbox.top + bbox.height / 2
+}
+
+/// Smallest box enclosing all `boxes`
+fn enclosing_box<'a>(boxes: impl IntoIterator<Item = &'a BoundingBox>) -> BoundingBox {
+    let (mut left, mut top, mut right, mut bottom) = (u32::MAX, u32::MAX, 0, 0);
+    for bbox in boxes {
+        left = left.min(bbox.left);
+        top = top.min(bbox.top);
+        right = right.max(bbox.right());
+        bottom = bottom.max(bbox.bottom());
+    }
+    if left > right {
+        return BoundingBox {
+            left: 0,
+            top: 0,
+            width: 0,
+            height: 0,
+        };
+    }
+    BoundingBox {
+        left,
+        top,
+        width: right - left,
+        height: bottom - top,
+    }
+}
+
+/// Sorted positions with those within [`LINE_TOLERANCE`] of each other
+/// merged into their mean
+fn cluster_positions(mut positions: Vec<u32>) -> Vec<u32> {
+    positions.sort_unstable();
+    let mut clusters: Vec<Vec<u32>> = Vec::new();
+    for position in positions {
+        match clusters.last_mut() {
+            Some(cluster) if position - cluster[cluster.len() - 1] <= LINE_TOLERANCE => {
+                cluster.push(position)
+            }
+            _ => clusters.push(vec![position]),
+        }
+    }
+    clusters
+        .iter()
+        .map(|cluster| cluster.iter().sum::<u32>() / cluster.len() as u32)
+        .collect()
+}
+
+/// Join word texts with spaces
+fn join_words(words: &[&OcrWord]) -> String {
+    words
+        .iter()
+        .map(|word| word.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Build one table from a connected set of ruled lines
+///
+/// Returns the table and the indices of the words placed in it.
+fn ruled_table(
+    horizontal: &[BoundingBox],
+    vertical: &[BoundingBox],
+    words: &[&OcrWord],
+) -> Option<(Table, Vec<usize>)> {
+    let row_bounds = cluster_positions(horizontal.iter().map(center_y).collect());
+    let column_bounds = cluster_positions(vertical.iter().map(center_x).collect());
+    let rows = row_bounds.len().saturating_sub(1);
+    let columns = column_bounds.len().saturating_sub(1);
+    if rows == 0 || columns == 0 || (rows < MIN_ROWS && columns < MIN_COLUMNS) {
+        return None;
+    }
+    let near = |a: u32, b: u32| a.abs_diff(b) <= LINE_TOLERANCE;
+
+    // A line separates two neighbouring cells if it covers the middle of
+    // their shared edge; where it does not, the cells are merged
+    let row_middle = |r: usize| (row_bounds[r] + row_bounds[r + 1]) / 2;
+    let column_middle = |c: usize| (column_bounds[c] + column_bounds[c + 1]) / 2;
+    let separated_horizontally = |r: usize, boundary: usize| {
+        vertical.iter().any(|line| {
+            near(center_x(line), column_bounds[boundary])
+                && line.top <= row_middle(r)
+                && line.bottom() >= row_middle(r)
+        })
+    };
+    let separated_vertically = |boundary: usize, c: usize| {
+        horizontal.iter().any(|line| {
+            near(center_y(line), row_bounds[boundary])
+                && line.left <= column_middle(c)
+                && line.right() >= column_middle(c)
+        })
+    };
+
+    let mut parent: Vec<usize> = (0..rows * columns).collect();
+    for r in 0..rows {
+        for c in 0..columns {
+            if c + 1 < columns && !separated_horizontally(r, c + 1) {
+                let (a, b) = (
+                    root(&mut parent, r * columns + c),
+                    root(&mut parent, r * columns + c + 1),
+                );
+                parent[a] = b;
+            }
+            if r + 1 < rows && !separated_vertically(r + 1, c) {
+                let (a, b) = (
+                    root(&mut parent, r * columns + c),
+                    root(&mut parent, (r + 1) * columns + c),
+                );
+                parent[a] = b;
+            }
+        }
+    }
+
+    // Each merged region becomes one cell spanning its bounding rows and
+    // columns, keyed by the region's root
+    let mut regions: std::collections::BTreeMap<usize, (usize, usize, usize, usize)> =
+        Default::default();
+    for r in 0..rows {
+        for c in 0..columns {
+            let region = root(&mut parent, r * columns + c);
+            let extent = regions.entry(region).or_insert((r, c, r, c));
+            extent.0 = extent.0.min(r);
+            extent.1 = extent.1.min(c);
+            extent.2 = extent.2.max(r);
+            extent.3 = extent.3.max(c);
+        }
+    }
+
+    let bbox = BoundingBox {
+        left: column_bounds[0],
+        top: row_bounds[0],
+        width: column_bounds[columns] - column_bounds[0],
+        height: row_bounds[rows] - row_bounds[0],
+    };
+
+    let mut cell_words: std::collections::HashMap<usize, Vec<&OcrWord>> = Default::default();
+    let mut placed = Vec::new();
+    for (index, word) in words.iter().enumerate() {
+        let (x, y) = (center_x(&word.bbox), center_y(&word.bbox));
+        if x < bbox.left || x >= bbox.right() || y < bbox.top || y >= bbox.bottom() {
+            continue;
+        }
+        let r = row_bounds.partition_point(|&bound| bound <= y) - 1;
+        let c = column_bounds.partition_point(|&bound| bound <= x) - 1;
+        let region = root(&mut parent, r.min(rows - 1) * columns + c.min(columns - 1));
+        cell_words.entry(region).or_default().push(word);
+        placed.push(index);
+    }
+
+    let mut cells: Vec<TableCell> = regions
+        .iter()
+        .map(|(region, &(top, left, bottom, right))| TableCell {
+            row: top,
+            column: left,
+            row_span: bottom - top + 1,
+            column_span: right - left + 1,
+            text: cell_words
+                .get(region)
+                .map(|words| join_words(words))
+                .unwrap_or_default(),
+            bbox: BoundingBox {
+                left: column_bounds[left],
+                top: row_bounds[top],
+                width: column_bounds[right + 1] - column_bounds[left],
+                height: row_bounds[bottom + 1] - row_bounds[top],
+            },
+        })
+        .collect();
+    cells.sort_by_key(|cell| (cell.row, cell.column));
+
+    Some((
+        Table {
+            rows,
+            columns,
+            bbox,
+            ruled: true,
+            cells,
+        },
+        placed,
+    ))
+}
+
+/// Tables formed by the ruled lines of a grid
+///
+/// Lines that meet are grouped, and each group with enough rows and
+/// columns becomes a table. Returns the tables and the indices of the
+/// words placed in them.
+fn ruled_tables(grid: &TableGrid, words: &[&OcrWord]) -> (Vec<Table>, Vec<usize>) {
+    let (horizontal, vertical) = (&grid.horizontal, &grid.vertical);
+    let count = horizontal.len() + vertical.len();
+    let mut parent: Vec<usize> = (0..count).collect();
+    for (h, line) in horizontal.iter().enumerate() {
+        for (v, column) in vertical.iter().enumerate() {
+            let meets = center_x(column) + LINE_TOLERANCE >= line.left
+                && center_x(column) <= line.right() + LINE_TOLERANCE
+                && center_y(line) + LINE_TOLERANCE >= column.top
+                && center_y(line) <= column.bottom() + LINE_TOLERANCE;
+            if meets {
+                let (a, b) = (
+                    root(&mut parent, h),
+                    root(&mut parent, horizontal.len() + v),
+                );
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut groups: std::collections::BTreeMap<usize, (Vec<BoundingBox>, Vec<BoundingBox>)> =
+        Default::default();
+    for i in 0..count {
+        let group = groups.entry(root(&mut parent, i)).or_default();
+        if i < horizontal.len() {
+            group.0.push(horizontal[i]);
+        } else {
+            group.1.push(vertical[i - horizontal.len()]);
+        }
+    }
+
+    let mut tables = Vec::new();
+    let mut placed = Vec::new();
+    for (group_horizontal, group_vertical) in groups.values() {
+        if let Some((table, words_placed)) = ruled_table(group_horizontal, group_vertical, words) {
+            tables.push(table);
+            placed.extend(words_placed);
+        }
+    }
+    (tables, placed)
+}
+
+/// Words at about the same height, left to right
+struct Row<'a> {
+    words: Vec<&'a OcrWord>,
+    top: u32,
+    bottom: u32,
+}
+
+/// A run of words in a row separated from its neighbours by a wide gap
+struct Segment<'a> {
+    words: Vec<&'a OcrWord>,
+    left: u32,
+    right: u32,
+}
+
+impl<'a> Row<'a> {
+    fn height(&self) -> u32 {
+        self.bottom - self.top
+    }
+
+    /// Split the row into segments at gaps wider than [`CELL_GAP`] word
+    /// heights
+    fn segments(&self) -> Vec<Segment<'a>> {
+        let mut heights: Vec<u32> = self.words.iter().map(|w| w.bbox.height).collect();
+        heights.sort_unstable();
+        let gap = heights[heights.len() / 2] as f32 * CELL_GAP;
+
+        let mut segments: Vec<Segment> = Vec::new();
+        for &word in &self.words {
+            match segments.last_mut() {
+                Some(segment) if (word.bbox.left as f32 - segment.right as f32) <= gap => {
+                    segment.right = segment.right.max(word.bbox.right());
+                    segment.words.push(word);
+                }
+                _ => segments.push(Segment {
+                    words: vec![word],
+                    left: word.bbox.left,
+                    right: word.bbox.right(),
+                }),
+            }
+        }
+        segments
+    }
+}
+
+/// Group text lines at the same height into rows, top to bottom
+fn build_rows<'a>(lines: Vec<Vec<&'a OcrWord>>) -> Vec<Row<'a>> {
+    let mut lines: Vec<Row> = lines
+        .into_iter()
+        .filter(|words| !words.is_empty())
+        .map(|words| {
+            let bbox = enclosing_box(words.iter().map(|w| &w.bbox));
+            Row {
+                words,
+                top: bbox.top,
+                bottom: bbox.bottom(),
+            }
+        })
+        .collect();
+    lines.sort_by_key(|line| (line.top + line.bottom) / 2);
+
+    let mut rows: Vec<Row> = Vec::new();
+    for line in lines {
+        if let Some(row) = rows.last_mut() {
+            let overlap = row.bottom.min(line.bottom) as i64 - row.top.max(line.top) as i64;
+            if overlap * 2 >= row.height().min(line.height()) as i64 {
+                row.top = row.top.min(line.top);
+                row.bottom = row.bottom.max(line.bottom);
+                row.words.extend(line.words);
+                continue;
+            }
+        }
+        rows.push(line);
+    }
+    for row in &mut rows {
+        row.words.sort_by_key(|word| word.bbox.left);
+    }
+    rows
+}
+
+/// Column extents shared by the rows' segments
+///
+/// A column gap is an x range crossed by segments in at most a quarter of
+/// the rows, so a few spanning cells do not hide it.
+fn column_extents(rows: &[Vec<Segment>]) -> Vec<(u32, u32)> {
+    let left = rows.iter().flatten().map(|s| s.left).min().unwrap_or(0);
+    let right = rows.iter().flatten().map(|s| s.right).max().unwrap_or(0);
+    let mut coverage = vec![0usize; (right - left) as usize + 1];
+    for row in rows {
+        let mut covered = vec![false; coverage.len()];
+        for segment in row {
+            for x in segment.left..=segment.right {
+                covered[(x - left) as usize] = true;
+            }
+        }
+        for (count, covered) in coverage.iter_mut().zip(covered) {
+            *count += covered as usize;
+        }
+    }
+
+    let max_crossing = (rows.len() as f32 * SPANNING_ROW_SHARE) as usize;
+    let mut columns = Vec::new();
+    let mut start: Option<u32> = None;
+    for (offset, &count) in coverage.iter().enumerate() {
+        let x = left + offset as u32;
+        match (count > max_crossing, start) {
+            (true, None) => start = Some(x),
+            (false, Some(column_start)) => {
+                columns.push((column_start, x));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(column_start) = start {
+        columns.push((column_start, right));
+    }
+    columns
+}
+
+/// Build a table from consecutive rows of segments
+///
+/// At least two columns must each hold text in [`MIN_ALIGNED_ROWS`] rows;
+/// two lines that happen to have a wide gap are not a table.
+fn aligned_table(rows: &[Row], segments: Vec<Vec<Segment>>) -> Option<Table> {
+    let columns = column_extents(&segments);
+    let aligned_columns = columns
+        .iter()
+        .filter(|&&(left, right)| {
+            segments
+                .iter()
+                .filter(|row| row.iter().any(|s| s.left < right && s.right > left))
+                .count()
+                >= MIN_ALIGNED_ROWS
+        })
+        .count();
+    if aligned_columns < MIN_COLUMNS {
+        return None;
+    }
+
+    let mut cells = Vec::new();
+    for (r, (row, row_segments)) in rows.iter().zip(segments).enumerate() {
+        // Columns each segment overlaps, or the nearest one for segments
+        // that fall entirely within a gap
+        let mut placed: Vec<(usize, usize, Vec<&OcrWord>)> = Vec::new();
+        for segment in row_segments {
+            let overlapping: Vec<usize> = columns
+                .iter()
+                .enumerate()
+                .filter(|(_, &(left, right))| segment.left < right && segment.right > left)
+                .map(|(c, _)| c)
+                .collect();
+            let (first, last) = match (overlapping.first(), overlapping.last()) {
+                (Some(&first), Some(&last)) => (first, last),
+                _ => {
+                    let middle = (segment.left + segment.right) / 2;
+                    let nearest = columns
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, &(left, right))| ((left + right) / 2).abs_diff(middle))
+                        .map(|(c, _)| c)?;
+                    (nearest, nearest)
+                }
+            };
+            // Segments sharing a column belong to the same cell
+            match placed.last_mut() {
+                Some(previous) if previous.1 >= first => {
+                    previous.1 = previous.1.max(last);
+                    previous.2.extend(segment.words);
+                }
+                _ => placed.push((first, last, segment.words)),
+            }
+        }
+
+        let mut next_column = 0;
+        let push_empty = |cells: &mut Vec<TableCell>, from: usize, to: usize| {
+            for (c, &(left, right)) in columns.iter().enumerate().take(to).skip(from) {
+                cells.push(TableCell {
+                    row: r,
+                    column: c,
+                    row_span: 1,
+                    column_span: 1,
+                    text: String::new(),
+                    bbox: BoundingBox {
+                        left,
+                        top: row.top,
+                        width: right - left,
+                        height: row.height(),
+                    },
+                });
+            }
+        };
+        for (first, last, words) in placed {
+            push_empty(&mut cells, next_column, first);
+            cells.push(TableCell {
+                row: r,
+                column: first,
+                row_span: 1,
+                column_span: last - first + 1,
+                text: join_words(&words),
+                bbox: enclosing_box(words.iter().map(|w| &w.bbox)),
+            });
+            next_column = last + 1;
+        }
+        push_empty(&mut cells, next_column, columns.len());
+    }
+
+    let bbox = enclosing_box(
+        rows.iter()
+            .flat_map(|row| row.words.iter().map(|word| &word.bbox)),
+    );
+    Some(Table {
+        rows: rows.len(),
+        columns: columns.len(),
+        bbox,
+        ruled: false,
+        cells,
+    })
+}
+
+/// Tables formed by aligned text without ruling
+///
+/// Runs of consecutive rows that split into at least two cells, with no
+/// wide vertical gap between them, are taken as tables.
+fn aligned_tables(rows: Vec<Row>) -> Vec<Table> {
+    let mut tables = Vec::new();
+    let mut run: Vec<Row> = Vec::new();
+    let mut flush = |run: &mut Vec<Row>| {
+        if run.len() >= MIN_ALIGNED_ROWS {
+            let segments = run.iter().map(Row::segments).collect();
+            tables.extend(aligned_table(run, segments));
+        }
+        run.clear();
+    };
+
+    for row in rows {
+        let is_tabular = row.segments().len() >= MIN_COLUMNS;
+        let follows = match run.last() {
+            Some(previous) => {
+                let gap = row.top.saturating_sub(previous.bottom) as f32;
+                gap <= previous.height().max(row.height()) as f32 * MAX_ROW_GAP
+            }
+            None => true,
+        };
+        if !is_tabular || !follows {
+            flush(&mut run);
+        }
+        if is_tabular {
+            run.push(row);
+        }
+    }
+    flush(&mut run);
+    tables
+}
+
+/// Recognize tables on a page
+///
+/// Ruled tables are built from the grid lines first; the remaining words
+/// are then searched for tables laid out by alignment alone.
+///
+/// # Arguments
+/// * `blocks` - The page layout, in source image coordinates
+/// * `grid` - Ruled lines found by line removal, in the same coordinates
+///
+/// # Returns
+/// The tables found, top to bottom
+pub fn detect_tables(blocks: &[OcrBlock], grid: Option<&TableGrid>) -> Vec<Table> {
+    let words: Vec<&OcrWord> = blocks
+        .iter()
+        .flat_map(|block| &block.paragraphs)
+        .flat_map(|paragraph| &paragraph.lines)
+        .flat_map(|line| &line.words)
+        .filter(|word| !word.text.trim().is_empty())
+        .collect();
+
+    let (mut tables, placed) = match grid {
+        Some(grid) => ruled_tables(grid, &words),
+        None => (Vec::new(), Vec::new()),
+    };
+    let mut is_placed = vec![false; words.len()];
+    for index in placed {
+        is_placed[index] = true;
+    }
+
+    // Lines of the words left over, block by block in reading order
+    let mut index = 0;
+    for block in blocks {
+        let mut lines: Vec<Vec<&OcrWord>> = Vec::new();
+        for line in block
+            .paragraphs
+            .iter()
+            .flat_map(|paragraph| &paragraph.lines)
+        {
+            let mut remaining = Vec::new();
+            for word in line.words.iter().filter(|w| !w.text.trim().is_empty()) {
+                if !is_placed[index] {
+                    remaining.push(word);
+                }
+                index += 1;
+            }
+            lines.push(remaining);
+        }
+        tables.extend(aligned_tables(build_rows(lines)));
+    }
+
+    tables.sort_by_key(|table| (table.bbox.top, table.bbox.left));
+    log::debug!("[Table] Found {} table(s)", tables.len());
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::parse_tsv;
+    use crate::preprocessing::CoordinateMapper;
+
+    const LINE_HEIGHT: u32 = 20;
+    const LINE_SPACING: u32 = 30;
+
+    /// Layout with one block per entry; each line lists its words as
+    /// `(left, text)`, words being 10 pixels per character
+    fn layout(blocks: &[Vec<Vec<(u32, &str)>>]) -> Vec<OcrBlock> {
+        let mut tsv = String::new();
+        for block in blocks {
+            tsv.push_str("2\t1\t1\t0\t0\t0\t0\t0\t1000\t1000\t-1\t\n");
+            tsv.push_str("3\t1\t1\t1\t0\t0\t0\t0\t1000\t1000\t-1\t\n");
+            for (l, line) in block.iter().enumerate() {
+                let top = l as u32 * LINE_SPACING;
+                tsv.push_str(&format!(
+                    "4\t1\t1\t1\t1\t0\t0\t{}\t1000\t{}\t-1\t\n",
+                    top, LINE_HEIGHT
+                ));
+                for (left, text) in line {
+                    tsv.push_str(&format!(
+                        "5\t1\t1\t1\t1\t1\t{}\t{}\t{}\t{}\t90\t{}\n",
+                        left,
+                        top,
+                        text.len() as u32 * 10,
+                        LINE_HEIGHT,
+                        text
+                    ));
+                }
+            }
+        }
+        parse_tsv(&tsv, &CoordinateMapper::new(1000, 1000))
+    }
+
+    #[test]
+    fn aligned_columns_form_a_table() {
+        let blocks = layout(&[vec![
+            vec![(0, "Item"), (200, "Qty"), (400, "Price")],
+            vec![(0, "Apples"), (200, "3"), (400, "1.20")],
+            vec![(0, "Pears"), (200, "12"), (400, "0.80")],
+        ]]);
+        let tables = detect_tables(&blocks, None);
+        assert_eq!(tables.len(), 1);
+        assert_eq!((tables[0].rows, tables[0].columns), (3, 3));
+        assert_eq!(tables[0].to_matrix()[2], vec!["Pears", "12", "0.80"]);
+    }
+
+    #[test]
+    fn two_aligned_lines_are_not_a_table() {
+        let blocks = layout(&[vec![
+            vec![(0, "Item"), (200, "Qty")],
+            vec![(0, "Apples"), (200, "3")],
+        ]]);
+        assert!(detect_tables(&blocks, None).is_empty());
+    }
+
+    #[test]
+    fn side_by_side_text_columns_are_not_a_table() {
+        // Two columns of prose, each its own layout block
+        let column = |left: u32| {
+            (0..6)
+                .map(|_| vec![(left, "lorem"), (left + 60, "ipsum"), (left + 120, "dolor")])
+                .collect::<Vec<_>>()
+        };
+        let blocks = layout(&[column(0), column(400)]);
+        assert!(detect_tables(&blocks, None).is_empty());
+    }
+
+    /// Horizontal and vertical 2 px rules; vertical rules are given as
+    /// `(left, top, bottom)` so they can stop short
+    fn grid(horizontal: &[u32], vertical: &[(u32, u32, u32)], right: u32) -> TableGrid {
+        TableGrid {
+            horizontal: horizontal
+                .iter()
+                .map(|&top| BoundingBox {
+                    left: 0,
+                    top,
+                    width: right + 2,
+                    height: 2,
+                })
+                .collect(),
+            vertical: vertical
+                .iter()
+                .map(|&(left, top, bottom)| BoundingBox {
+                    left,
+                    top,
+                    width: 2,
+                    height: bottom - top,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn ruled_grid_with_a_missing_segment_merges_cells() {
+        // A 3x3 grid whose second vertical rule skips the middle row
+        let blocks = layout(&[vec![
+            vec![(20, "A"), (170, "B"), (320, "C")],
+            vec![(20, "Merged"), (320, "D")],
+            vec![(20, "E"), (170, "F"), (320, "G")],
+        ]]);
+        let grid = grid(
+            &[0, 25, 55, 85],
+            &[
+                (0, 0, 87),
+                (150, 0, 26),
+                (150, 55, 87),
+                (300, 0, 87),
+                (450, 0, 87),
+            ],
+            450,
+        );
+
+        let tables = detect_tables(&blocks, Some(&grid));
+        assert_eq!(tables.len(), 1);
+        let table = &tables[0];
+        assert!(table.ruled);
+        assert_eq!((table.rows, table.columns), (3, 3));
+        assert_eq!(table.cells.len(), 8);
+
+        let merged = table
+            .cells
+            .iter()
+            .find(|cell| (cell.row, cell.column) == (1, 0))
+            .unwrap();
+        assert_eq!((merged.row_span, merged.column_span), (1, 2));
+        assert_eq!(merged.text, "Merged");
+
+        assert_eq!(
+            table.to_matrix(),
+            vec![
+                vec!["A", "B", "C"],
+                vec!["Merged", "", "D"],
+                vec!["E", "F", "G"],
+            ]
+        );
+    }
+
+    #[test]
+    fn boxed_paragraph_is_not_a_table() {
+        let blocks = layout(&[vec![
+            vec![(20, "Note:"), (80, "keep"), (130, "this")],
+            vec![(20, "page"), (70, "dry.")],
+        ]]);
+        let grid = grid(&[0, 55], &[(0, 0, 57), (300, 0, 57)], 300);
+        assert!(detect_tables(&blocks, Some(&grid)).is_empty());
+    }
+}
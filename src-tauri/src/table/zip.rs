@@ -0,0 +1,224 @@
+//! Minimal ZIP archive writer
+//!
+//! Just enough of the format for XLSX packages: deflated entries, no
+//! directories, comments or ZIP64.
+
+use miniz_oxide::deflate::compress_to_vec;
+
+/// Deflate compression level (0-10)
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// ZIP "deflate" compression method
+const METHOD_DEFLATE: u16 = 8;
+
+/// Version needed to extract (2.0, for deflate)
+const VERSION: u16 = 20;
+
+/// MS-DOS date of 1980-01-01, the earliest a ZIP entry can carry
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+/// Central directory record of one entry
+struct Entry {
+    name: String,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Writes entries into an in-memory ZIP archive
+#[derive(Default)]
+pub struct ZipWriter {
+    data: Vec<u8>,
+    entries: Vec<Entry>,
+}
+
+/// CRC-32 (IEEE 802.3) of `bytes`
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+impl ZipWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file, compressed with deflate
+    pub fn add_file(&mut self, name: &str, contents: &[u8]) {
+        let compressed = compress_to_vec(contents, COMPRESSION_LEVEL);
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc32(contents),
+            compressed_size: compressed.len() as u32,
+            size: contents.len() as u32,
+            offset: self.data.len() as u32,
+        };
+
+        let out = &mut self.data;
+        put_u32(out, 0x0403_4b50);
+        put_u16(out, VERSION);
+        put_u16(out, 0); // flags
+        put_u16(out, METHOD_DEFLATE);
+        put_u16(out, 0); // time
+        put_u16(out, DOS_DATE);
+        put_u32(out, entry.crc);
+        put_u32(out, entry.compressed_size);
+        put_u32(out, entry.size);
+        put_u16(out, entry.name.len() as u16);
+        put_u16(out, 0); // extra field length
+        out.extend_from_slice(entry.name.as_bytes());
+        out.extend_from_slice(&compressed);
+
+        self.entries.push(entry);
+    }
+
+    /// Write the central directory and return the archive bytes
+    pub fn finish(mut self) -> Vec<u8> {
+        let directory_offset = self.data.len() as u32;
+        let out = &mut self.data;
+        for entry in &self.entries {
+            put_u32(out, 0x0201_4b50);
+            put_u16(out, VERSION); // version made by
+            put_u16(out, VERSION); // version needed
+            put_u16(out, 0); // flags
+            put_u16(out, METHOD_DEFLATE);
+            put_u16(out, 0); // time
+            put_u16(out, DOS_DATE);
+            put_u32(out, entry.crc);
+            put_u32(out, entry.compressed_size);
+            put_u32(out, entry.size);
+            put_u16(out, entry.name.len() as u16);
+            put_u16(out, 0); // extra field length
+            put_u16(out, 0); // comment length
+            put_u16(out, 0); // disk number
+            put_u16(out, 0); // internal attributes
+            put_u32(out, 0); // external attributes
+            put_u32(out, entry.offset);
+            out.extend_from_slice(entry.name.as_bytes());
+        }
+        let directory_size = out.len() as u32 - directory_offset;
+
+        put_u32(out, 0x0605_4b50);
+        put_u16(out, 0); // this disk
+        put_u16(out, 0); // disk with the central directory
+        put_u16(out, self.entries.len() as u16);
+        put_u16(out, self.entries.len() as u16);
+        put_u32(out, directory_size);
+        put_u32(out, directory_offset);
+        put_u16(out, 0); // comment length
+
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::inflate::decompress_to_vec;
+
+    fn u16_at(data: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes([data[offset], data[offset + 1]])
+    }
+
+    fn u32_at(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// Read every entry back through the central directory
+    fn read_archive(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let end = data.len() - 22;
+        assert_eq!(u32_at(data, end), 0x0605_4b50);
+        let count = u16_at(data, end + 10) as usize;
+        let directory_size = u32_at(data, end + 12) as usize;
+        let mut record = u32_at(data, end + 16) as usize;
+        assert_eq!(record + directory_size, end);
+
+        let mut files = Vec::new();
+        for _ in 0..count {
+            assert_eq!(u32_at(data, record), 0x0201_4b50);
+            let crc = u32_at(data, record + 16);
+            let compressed_size = u32_at(data, record + 20) as usize;
+            let size = u32_at(data, record + 24) as usize;
+            let name_len = u16_at(data, record + 28) as usize;
+            let local = u32_at(data, record + 42) as usize;
+            let name = &data[record + 46..record + 46 + name_len];
+
+            // The local header repeats the directory's sizes and name
+            assert_eq!(u32_at(data, local), 0x0403_4b50);
+            assert_eq!(u16_at(data, local + 8), METHOD_DEFLATE);
+            assert_eq!(u32_at(data, local + 14), crc);
+            assert_eq!(u32_at(data, local + 18) as usize, compressed_size);
+            assert_eq!(u32_at(data, local + 22) as usize, size);
+            assert_eq!(u16_at(data, local + 26) as usize, name_len);
+            let start = local + 30 + name_len + u16_at(data, local + 28) as usize;
+            assert_eq!(&data[local + 30..local + 30 + name_len], name);
+
+            let contents = decompress_to_vec(&data[start..start + compressed_size]).unwrap();
+            assert_eq!(contents.len(), size);
+            assert_eq!(crc32(&contents), crc);
+            files.push((String::from_utf8(name.to_vec()).unwrap(), contents));
+
+            record += 46
+                + name_len
+                + u16_at(data, record + 30) as usize
+                + u16_at(data, record + 32) as usize;
+        }
+        assert_eq!(record, end);
+        files
+    }
+
+    #[test]
+    fn crc32_known_answers() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414F_A339
+        );
+    }
+
+    #[test]
+    fn archive_round_trips() {
+        let sheet = "<row><c><v>1</v></c></row>".repeat(200);
+        let files = [
+            ("[Content_Types].xml", b"<Types/>".to_vec()),
+            ("xl/worksheets/sheet1.xml", sheet.into_bytes()),
+            ("empty.txt", Vec::new()),
+        ];
+
+        let mut zip = ZipWriter::new();
+        for (name, contents) in &files {
+            zip.add_file(name, contents);
+        }
+        let read = read_archive(&zip.finish());
+
+        assert_eq!(read.len(), files.len());
+        for ((name, contents), (read_name, read_contents)) in files.iter().zip(&read) {
+            assert_eq!(name, read_name);
+            assert_eq!(contents, read_contents);
+        }
+    }
+
+    #[test]
+    fn empty_archive_has_only_the_end_record() {
+        let data = ZipWriter::new().finish();
+        assert_eq!(data.len(), 22);
+        assert!(read_archive(&data).is_empty());
+    }
+}
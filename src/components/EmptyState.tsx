@@ -64,10 +64,13 @@ export const EmptyState: FC<EmptyStateProps> = ({
               value={language}
               onChange={(event) => onLanguageChange(event.target.value)}
             >
+              <option value="auto">Auto Detect</option>
               <option value="eng">English</option>
               <option value="eng_degraded">English (Degraded Images)</option>
               <option value="chi_sim">Simplified Chinese</option>
               <option value="chi_tra">Traditional Chinese</option>
+              <option value="eng+chi_sim">English + Simplified Chinese</option>
+              <option value="eng+jpn">English + Japanese</option>
               <option value="jpn">Japanese</option>
               <option value="kor">한국어</option>
               <option value="fra">Français</option>
@@ -108,6 +108,10 @@ export const AdvancedControls: FC<AdvancedControlsProps> = ({
               <option value="adaptive">Adaptive</option>
               <option value="mean">Mean</option>
               <option value="sauvola">Sauvola (Uneven Light) 🆕</option>
+              <option value="niblack">Niblack</option>
+              <option value="wolf">Wolf-Jolion</option>
+              <option value="bradley">Bradley-Roth</option>
+              <option value="nick">NICK</option>
             </select>
           </label>
         </div>
@@ -157,6 +161,12 @@ export const AdvancedControls: FC<AdvancedControlsProps> = ({
 
       {/* Third row: New features */}
       <div className="controls-row-mixed">
+        <Checkbox
+          label="🔄 Auto Rotate"
+          checked={params.detectOrientation}
+          onChange={(checked) => onParamChange('detectOrientation', checked)}
+        />
+
         <Checkbox
           label="📐 Deskew"
           checked={params.correctSkew}
@@ -174,6 +184,12 @@ export const AdvancedControls: FC<AdvancedControlsProps> = ({
           checked={params.adaptiveMode}
           onChange={(checked) => onParamChange('adaptiveMode', checked)}
         />
+
+        <Checkbox
+          label="🏆 Best Of"
+          checked={params.bestOf ?? false}
+          onChange={(checked) => onParamChange('bestOf', checked)}
+        />
       </div>
     </div>
   );
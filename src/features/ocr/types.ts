@@ -1,17 +1,105 @@
+// Mirrors the Rust definitions; the `get_processing_params_schema` command
+// returns their JSON Schema for regenerating these types
+export type BinarizationMethod =
+  | 'none' | 'otsu' | 'adaptive' | 'mean'
+  | 'sauvola' | 'niblack' | 'wolf' | 'bradley' | 'nick';
+export type MorphologyOperation = 'none' | 'erode' | 'dilate' | 'opening' | 'closing';
+export type SkewMethod = 'hough' | 'projection';
+export type ResampleFilter = 'lanczos' | 'bicubic';
+export type BackgroundMethod = 'closing' | 'median';
+export type FlattenMode = 'divide' | 'subtract';
+export type DropoutMode = 'auto' | 'drop' | 'keep';
+export type NamedHue = 'red' | 'orange' | 'yellow' | 'green' | 'cyan' | 'blue' | 'magenta';
+export type HueSpec = NamedHue | { from: number; to: number };  // Degrees; wraps when from > to
+
 export interface ProcessingParams {
-  contrast: number;
-  brightness: number;
-  sharpness: number;
-  binarizationMethod: string;  // "none" | "adaptive" | "otsu" | "mean" | "sauvola"
+  contrast: number;  // 0.1-5.0
+  brightness: number;  // -1.0-1.0
+  sharpness: number;  // 0.0-5.0
+  binarizationMethod: BinarizationMethod;
+  thresholdWindow?: number;  // Local threshold window in pixels
+  thresholdK?: number;  // Local threshold sensitivity
+  thresholdR?: number;  // Sauvola dynamic range (default 128)
   useClahe: boolean;
-  gaussianBlur: number;
+  claheTileGrid?: number;  // CLAHE tiles per side, 1-64 (default 8)
+  claheClipLimit?: number;  // CLAHE clip limit, 1.0-40.0 (default 2.0)
+  gaussianBlur: number;  // Sigma, 0.0-10.0
   bilateralFilter: boolean;
-  morphology: string;  // "none" | "erode" | "dilate" | "opening" | "closing"
-  language: string;
+  morphology: MorphologyOperation;
+  language: string;  // e.g. "eng", "eng+chi_sim" or "auto"
+  detectOrientation: boolean;  // Detect and fix 90°/180°/270° rotation
+  normalizeScale?: boolean;  // Resample to the target text height
+  targetTextHeight?: number;  // Pixels, 8-100 (default 24)
+  normalizePolarity?: boolean;  // Invert light-on-dark regions (dark mode)
+  colorDropout?: boolean;  // Fade out colors other than the dominant ink
+  normalizeBackground?: boolean;  // Flatten shadows and lighting gradients
+  removeLines?: boolean;  // Erase table and form grid lines
+  returnTableGrid?: boolean;  // Return the erased lines as tableGrid
+  detectTables?: boolean;  // Reconstruct tables from words and ruled lines
+  despeckle?: boolean;  // Remove specks and pinholes after binarization
+  despeckleBorder?: boolean;  // Also remove blobs touching the page border
   correctSkew: boolean;  // Skew correction
-  skewMethod: string;  // "hough" | "projection"
+  skewMethod: SkewMethod;
+  correctPerspective?: boolean;  // Detect a photographed page and rectify it
+  documentCorners?: [number, number][];  // Manual [x, y] corners: TL, TR, BR, BL
+  dewarp?: boolean;  // Straighten text lines curved by page curl
   removeBorders: boolean;  // Remove black borders
   adaptiveMode: boolean;  // Enable adaptive preprocessing
+  pdfDpi?: number;  // Rasterization DPI for PDF input (default 300)
+  tesseract?: TesseractOptions;
+  pipeline?: Pipeline;  // Explicit step order; overrides the fields above
+  debug?: boolean;  // Return a thumbnail of every pipeline stage
+  bestOf?: boolean;  // Try candidate pipelines and keep the most confident
+}
+
+// Preprocessing step configuration, tagged by `type`
+export type PipelineStepConfig =
+  | { type: 'perspective'; corners?: [number, number][] }
+  | { type: 'removeBorders' }
+  | { type: 'orientation' }
+  | { type: 'deskew'; method?: SkewMethod }
+  | { type: 'dewarp' }
+  | { type: 'normalizeScale'; targetHeight?: number; filter?: ResampleFilter }
+  | { type: 'normalizePolarity' }
+  | { type: 'colorDropout'; mode?: DropoutMode; hues?: HueSpec[] }
+  | { type: 'normalizeBackground'; method?: BackgroundMethod; mode?: FlattenMode; kernelSize?: number }
+  | { type: 'removeLines'; minLength?: number; returnGrid?: boolean }
+  | { type: 'bilateralFilter' }
+  | { type: 'gaussianBlur'; sigma: number }
+  | { type: 'brightness'; amount: number }
+  | { type: 'contrast'; factor: number }
+  | { type: 'sharpen'; amount: number }
+  | { type: 'clahe'; tileGrid?: number; clipLimit?: number }
+  | { type: 'morphology'; operation: MorphologyOperation }
+  | { type: 'binarize'; method: BinarizationMethod; window?: number; k?: number; r?: number }
+  | { type: 'despeckle'; speckSize?: number; fillHoles?: boolean; removeBorder?: boolean };
+
+export type PipelineStep = PipelineStepConfig & {
+  enabled?: boolean;  // Defaults to true
+};
+
+export interface Pipeline {
+  steps: PipelineStep[];
+}
+
+export interface TesseractOptions {
+  pageSegMode?: number;  // 1-13, e.g. 7 = single line, 11 = sparse text
+  engineMode?: number;  // 0 = legacy, 1 = LSTM, 2 = combined, 3 = default
+  charWhitelist?: string;
+  charBlacklist?: string;
+  userDefinedDpi?: number;  // 70-2400
+  preserveInterwordSpaces?: boolean;
+  variables?: Record<string, string>;  // Additional Tesseract variables
+}
+
+// Built-in presets: 'screenshot', 'scanned document', 'photo of whiteboard',
+// 'receipt', 'dark-mode UI'. Pass `preset` (and optional partial `overrides`)
+// to `perform_ocr` instead of `params`.
+export interface Preset {
+  name: string;
+  description: string;
+  builtIn: boolean;  // Built-in presets cannot be overwritten or deleted
+  params: ProcessingParams;
 }
 
 export interface ImageQualityMetrics {
@@ -19,12 +107,150 @@ export interface ImageQualityMetrics {
   contrastScore: number;    // 0-100, higher is better
   noiseLevel: number;       // 0-100, lower is better
   brightnessLevel: number;  // 0-255, average brightness
+  polarity: PolarityDetection;
+  illuminationUnevenness: number;  // 0-100, higher means shadows or gradients
 }
 
-export interface OcrResult {
+export interface PolarityDetection {
+  polarity: 'normal' | 'inverted' | 'mixed';  // 'inverted' = light text on dark
+  darkFraction: number;  // 0.0-1.0, share of the image in dark regions holding light text
+}
+
+// Pixel box in original image coordinates
+export interface BoundingBox {
+  left: number;
+  top: number;
+  width: number;
+  height: number;
+}
+
+export interface OcrWord {
+  text: string;
+  confidence: number;  // 0-100
+  bbox: BoundingBox;
+}
+
+export interface OcrLine {
+  text: string;
+  confidence: number;  // 0-100, mean of word confidences
+  bbox: BoundingBox;
+  words: OcrWord[];
+}
+
+export interface OcrParagraph {
+  bbox: BoundingBox;
+  lines: OcrLine[];
+}
+
+export interface OcrBlock {
+  bbox: BoundingBox;
+  paragraphs: OcrParagraph[];
+}
+
+export interface ScriptDetection {
+  script: string;  // Tesseract script name, e.g. "Latin", "Han"
+  confidence: number;
+}
+
+export interface ExifOrientation {
+  exifValue: number;  // EXIF Orientation tag (2-8)
+  rotationDegrees: number;  // Clockwise rotation, applied before the flip
+  flipHorizontal: boolean;
+}
+
+export interface OrientationDetection {
+  rotationDegrees: number;  // Clockwise rotation applied: 0, 90, 180 or 270
+  confidence: number;
+  method: 'osd' | 'projection';
+}
+
+export interface OcrPageResult {
+  pageNumber: number;  // 1-based page (or frame) number
   text: string;
   processedImagePath: string;
   qualityMetrics?: ImageQualityMetrics;
+  exifOrientation?: ExifOrientation;  // Applied on load; boxes match the oriented image
+  language: string;  // Tesseract language used, e.g. "chi_sim+eng"
+  script?: ScriptDetection;  // Set in "auto" language mode
+  orientation?: OrientationDetection;  // Set when detectOrientation is enabled
+  blocks: OcrBlock[];
+  imageWidth: number;
+  imageHeight: number;
+  scaleFactor: number;  // Resampling applied by scale normalization (1.0 if none)
+  dewarp?: DewarpReport;  // Set when dewarping straightened curved lines
+  tableGrid?: TableGrid;  // Set when line removal was asked to return the grid
+  tables: Table[];  // Filled when detectTables is enabled
+  stages: PipelineStage[];  // Filled in debug mode, in run order
+  bestOf?: BestOfResult;  // Set in best-of mode
+}
+
+export interface TableGrid {
+  horizontal: BoundingBox[];  // Top to bottom, in source image coordinates
+  vertical: BoundingBox[];  // Left to right
+}
+
+export interface TableCell {
+  row: number;  // 0-based top row
+  column: number;  // 0-based left column
+  rowSpan: number;  // Rows covered; more than 1 for merged cells
+  columnSpan: number;
+  text: string;
+  bbox: BoundingBox;
+}
+
+export interface Table {
+  rows: number;
+  columns: number;
+  bbox: BoundingBox;
+  ruled: boolean;  // Built from ruled lines rather than word alignment
+  cells: TableCell[];  // Row-major; together they cover every position once
+}
+
+export type TableFormat = 'csv' | 'markdown' | 'xlsx';
+
+export interface DewarpReport {
+  lines: number;  // Text lines the correction was fitted to
+  curvatureBefore: number;  // Mean deviation from straight, in pixels
+  curvatureAfter: number;
+  curvatureRemoved: number;  // 0-1
+}
+
+export interface CandidateScore {
+  label: string;  // e.g. "sauvola, no deskew"
+  meanConfidence: number;  // 0-100
+  dictionaryHitRate?: number;  // 0-1, unset without a system word list
+  wordCount: number;
+  characterCount: number;
+  score: number;  // 0-1, quality scaled by characters read relative to the best candidate
+}
+
+export interface BestOfResult {
+  winner: CandidateScore;
+  others: CandidateScore[];  // Best first
+}
+
+// Output of one preprocessing stage (debug mode)
+export interface PipelineStage {
+  name: string;  // Step type, e.g. "deskew"
+  params: Record<string, unknown>;  // Parameters the stage ran with
+  durationMs: number;
+  thumbnailPath: string;  // Downscaled PNG, load with convertFileSrc
 }
 
+export interface OcrResult {
+  text: string;  // Combined text of all pages
+  pages: OcrPageResult[];
+  formattedOutput?: string;  // Set when outputFormat is "hocr" | "alto" | "tsv"
+  trace: TraceEvent[];  // Step timings and automatic decisions, in order
+}
+
+export interface TraceEvent {
+  page?: number;  // Unset for whole-document events
+  step: string;  // e.g. "deskew", "adaptive", "recognize"
+  durationMs?: number;
+  reason?: string;  // e.g. "blur_score 22.0 < 30 → sharpness 2.0"
+}
+
+export type OutputFormat = 'text' | 'hocr' | 'alto' | 'tsv';
+
 export type TextDisplayMode = 'original' | 'optimized';
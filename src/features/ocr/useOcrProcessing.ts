@@ -32,10 +32,13 @@ const DEFAULT_PARAMS: ProcessingParams = {
   sharpness: 1.2,
   binarizationMethod: 'otsu',  // Use Otsu binarization
   useClahe: false,
+  claheTileGrid: 8,
+  claheClipLimit: 2.0,
   gaussianBlur: 0.5,
   bilateralFilter: false,
   morphology: 'none',
   language: 'eng',
+  detectOrientation: false,  // Don't rotate sideways or upside-down pages by default
   correctSkew: true,  // Enable skew correction by default
   skewMethod: 'projection',  // Use projection method (faster and more reliable)
   removeBorders: false,  // Don't remove borders by default
@@ -169,7 +172,8 @@ export const useOcrProcessing = (options: UseOcrProcessingOptions = {}) => {
       setOcrText(reflowedText);
       onTextChange?.(reflowedText);
 
-      const processedUrl = convertFileSrc(result.processedImagePath);
+      const processedImagePath = result.pages[0]?.processedImagePath;
+      const processedUrl = processedImagePath ? convertFileSrc(processedImagePath) : '';
       setProcessedImageUrl(processedUrl);
       setProcessingStatus('Complete!');
 